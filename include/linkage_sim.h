@@ -0,0 +1,392 @@
+#ifndef LINKAGE_SIM_H
+#define LINKAGE_SIM_H
+
+/* generated by the gen-header binary with cbindgen, do not edit */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum ScissorJointRole {
+  ScissorJointRole_AOrigin,
+  ScissorJointRole_BOrigin,
+  ScissorJointRole_Pivot,
+  ScissorJointRole_AEnd,
+  ScissorJointRole_BEnd,
+} ScissorJointRole;
+
+typedef enum LinkageSimError {
+  LinkageSimError_Ok = 0,
+  LinkageSimError_Unknown = 1,
+  LinkageSimError_NullPointer = 2,
+  LinkageSimError_NoElement = 3,
+  LinkageSimError_InvalidDimension = 4,
+  LinkageSimError_InvalidInput = 5,
+  LinkageSimError_CannotAssemble = 6,
+  LinkageSimError_Panic = 7,
+  LinkageSimError_IndexOutOfRange = 8,
+  LinkageSimError_NotSolved = 9,
+  LinkageSimError_BufferTooSmall = 10,
+} LinkageSimError;
+
+typedef struct Mechanism Mechanism;
+
+typedef struct Scissor Scissor;
+
+typedef struct triangle_side {
+  double radius;
+  double theta;
+  uint8_t radius_unknown;
+  uint8_t theta_unknown;
+} triangle_side;
+
+typedef struct triangle_description {
+  struct triangle_side a;
+  struct triangle_side b;
+  struct triangle_side c;
+} triangle_description;
+
+typedef struct solve_triangle_return {
+  ptrdiff_t error;
+  size_t num_solutions;
+  struct triangle_description solutions[2];
+} solve_triangle_return;
+
+typedef struct ScissorDimension {
+  double a;
+  double b;
+  double c;
+  double d;
+} ScissorDimension;
+
+typedef struct line {
+  double x1;
+  double y1;
+  double x2;
+  double y2;
+} line;
+
+typedef struct solve_scissor_return {
+  ptrdiff_t error;
+  size_t num_lines;
+  const struct line *lines;
+} solve_scissor_return;
+
+typedef struct AngulatedScissorDimension {
+  double a;
+  double b;
+  double c;
+  double d;
+  double kink_a;
+  double kink_b;
+} AngulatedScissorDimension;
+
+typedef struct ScissorJoint {
+  size_t element_index;
+  enum ScissorJointRole role;
+  double x;
+  double y;
+} ScissorJoint;
+
+typedef struct solve_scissor_joints_return {
+  ptrdiff_t error;
+  size_t num_joints;
+  const struct ScissorJoint *joints;
+} solve_scissor_joints_return;
+
+typedef struct scissor_sweep_sample {
+  ptrdiff_t error;
+  double x;
+  double y;
+} scissor_sweep_sample;
+
+typedef struct MechInput {
+  size_t joint_index;
+  size_t linkage_index;
+  double angle;
+} MechInput;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+ptrdiff_t run_scissor_test(void);
+
+ptrdiff_t run_triangle_test(void);
+
+ptrdiff_t run_crosslink_test(void);
+
+int32_t add(int32_t a, int32_t b);
+
+const char *linkage_sim_last_error_message(void);
+
+struct solve_triangle_return solve_triangle(struct triangle_description triangle);
+
+const struct ScissorDimension *create_scissor_dimension_array(size_t size);
+
+/**
+ * array must be created by create_scissor_dimension_array with size len (or be any array of len ScissorDimension)
+ * element at index is written to out, out is not changed on error
+ * returns LinkageSimError, IndexOutOfRange if index >= len
+ *
+ * # Safety
+ *
+ * array must be null or point to len readable ScissorDimension, out must be null or point to a writable ScissorDimension.
+ */
+ptrdiff_t get_scissor_dimension_array_element(const struct ScissorDimension *array,
+                                              size_t len,
+                                              size_t index,
+                                              struct ScissorDimension *out);
+
+/**
+ * same as get_scissor_dimension_array_element, value is written at index
+ *
+ * # Safety
+ *
+ * array must be null or point to len writable ScissorDimension.
+ */
+ptrdiff_t set_scissor_dimension_array_element(struct ScissorDimension *array,
+                                              size_t len,
+                                              size_t index,
+                                              struct ScissorDimension value);
+
+/**
+ * array must be created by create_scissor_dimension_array with size len
+ *
+ * # Safety
+ *
+ * array must be null or returned by create_scissor_dimension_array with size len, and must not be used after this call.
+ */
+void free_scissor_dimension_array(struct ScissorDimension *array,
+                                  size_t len);
+
+/**
+ * # Safety
+ *
+ * array must be null or point to len readable ScissorDimension.
+ */
+struct solve_scissor_return solve_from_scissor_dimension_array(const struct ScissorDimension *array,
+                                                               size_t len,
+                                                               double input_radius,
+                                                               double input_theta);
+
+/**
+ * same as solve_from_scissor_dimension_array but each bar is returned as 2 lines (origin to pivot, pivot to endpoint)
+ * lines are ordered a inner, a outer, b inner, b outer for each element
+ *
+ * # Safety
+ *
+ * array must be null or point to len readable AngulatedScissorDimension.
+ */
+struct solve_scissor_return solve_from_angulated_scissor_dimension_array(const struct AngulatedScissorDimension *array,
+                                                                         size_t len,
+                                                                         double input_radius,
+                                                                         double input_theta);
+
+/**
+ * ret must be returned by solve_from_scissor_dimension_array or solve_from_angulated_scissor_dimension_array
+ *
+ * # Safety
+ *
+ * ret must be returned by solve_from_scissor_dimension_array or solve_from_angulated_scissor_dimension_array without modification and must be freed only once.
+ */
+void free_solve_scissor_return(struct solve_scissor_return ret);
+
+/**
+ * joints are ordered as Scissor::get_joints
+ *
+ * # Safety
+ *
+ * array must be null or point to len readable ScissorDimension.
+ */
+struct solve_scissor_joints_return solve_joints_from_scissor_dimension_array(const struct ScissorDimension *array,
+                                                                             size_t len,
+                                                                             double input_radius,
+                                                                             double input_theta);
+
+/**
+ * ret must be returned by solve_joints_from_scissor_dimension_array
+ *
+ * # Safety
+ *
+ * ret must be returned by solve_joints_from_scissor_dimension_array without modification and must be freed only once.
+ */
+void free_solve_scissor_joints_return(struct solve_scissor_joints_return ret);
+
+/**
+ * null is returned if the scissor cannot be created, see linkage_sim_last_error_message
+ *
+ * # Safety
+ *
+ * array must be null or point to len readable ScissorDimension.
+ */
+struct Scissor *scissor_new(const struct ScissorDimension *array, size_t len);
+
+/**
+ * on error, the scissor keeps the previous solution
+ *
+ * # Safety
+ *
+ * scissor must be null or a live handle returned by scissor_new, not used by another thread during the call.
+ */
+ptrdiff_t scissor_solve(struct Scissor *scissor,
+                        double input_radius,
+                        double input_theta);
+
+/**
+ * # Safety
+ *
+ * scissor must be null or a live handle returned by scissor_new, x and y must be null or point to a writable double.
+ */
+ptrdiff_t scissor_get_endpoint(const struct Scissor *scissor,
+                               double *x,
+                               double *y);
+
+/**
+ * joints are written to buffer in the order of Scissor::get_joints
+ * num_joints : number of joints of the scissor, written even if capacity is too small so that the caller can allocate the buffer
+ * returns BufferTooSmall and writes no joint if capacity < num_joints
+ *
+ * # Safety
+ *
+ * scissor must be null or a live handle returned by scissor_new, num_joints must be null or point to a writable size_t, buffer must be null or point to capacity writable ScissorJoint.
+ */
+ptrdiff_t scissor_get_joints(const struct Scissor *scissor,
+                             struct ScissorJoint *buffer,
+                             size_t capacity,
+                             size_t *num_joints);
+
+/**
+ * solve the scissor for every input and write the endpoint of each sample, the handle itself is not changed
+ * input_radii, input_thetas and samples have num_samples elements
+ * joints : optional (can be null), joints of sample i are written from joints[i * num_joints] in the order of Scissor::get_joints
+ * num_joints is JOINTS_PER_ELEMENT * number of elements, joints_capacity must be at least num_samples * num_joints
+ * joints of failed samples are not written
+ * returns error only if the arguments are invalid, failure of each sample is in its error
+ *
+ * # Safety
+ *
+ * scissor must be null or a live handle returned by scissor_new, input_radii and input_thetas must be null or point to num_samples readable double,
+ * samples must be null or point to num_samples writable scissor_sweep_sample, joints must be null or point to joints_capacity writable ScissorJoint.
+ */
+ptrdiff_t scissor_solve_sweep(const struct Scissor *scissor,
+                              const double *input_radii,
+                              const double *input_thetas,
+                              size_t num_samples,
+                              struct scissor_sweep_sample *samples,
+                              struct ScissorJoint *joints,
+                              size_t joints_capacity);
+
+/**
+ * # Safety
+ *
+ * scissor must be null or a handle returned by scissor_new that is not freed yet, it must not be used after this call.
+ */
+void scissor_free(struct Scissor *scissor);
+
+/**
+ * points : x and y of the joints of every linkage in its local frame, linkage by linkage (2 * sum of num_points doubles)
+ * num_points : number of joints of each linkage (num_linkages elements), lines of the linkage connect consecutive joints
+ * connection_linkages and connection_indexes : (linkage index, joint index in the linkage) connected by each pin joint, joint by joint (sum of num_connections elements)
+ * num_connections : number of connections of each pin joint (num_joints elements)
+ * null is returned if the mechanism cannot be created, see linkage_sim_last_error_message
+ *
+ * # Safety
+ *
+ * every pointer must be null or point to the number of readable elements above, pointers of empty arrays can be null.
+ */
+struct Mechanism *mechanism_new(const double *points,
+                                const size_t *num_points,
+                                size_t num_linkages,
+                                const size_t *connection_linkages,
+                                const size_t *connection_indexes,
+                                const size_t *num_connections,
+                                size_t num_joints);
+
+/**
+ * fix the joint to the ground at (x, y)
+ *
+ * # Safety
+ *
+ * mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call.
+ */
+ptrdiff_t mechanism_fix_joint(struct Mechanism *mechanism,
+                              size_t joint,
+                              double x,
+                              double y);
+
+/**
+ * add SolveHint, the joint of the dyad is solved to the side where (joint_to_0 - joint_from) x (joint_to_1 - joint_from) is positive, negative if invert
+ *
+ * # Safety
+ *
+ * mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call.
+ */
+ptrdiff_t mechanism_add_hint(struct Mechanism *mechanism,
+                             size_t joint_from,
+                             size_t joint_to_0,
+                             size_t joint_to_1,
+                             bool invert);
+
+/**
+ * solve the mechanism at the inputs (num_inputs elements), on error the mechanism keeps the previous solution
+ *
+ * # Safety
+ *
+ * mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call,
+ * inputs must be null or point to num_inputs readable MechInput (can be null if num_inputs is 0).
+ */
+ptrdiff_t mechanism_solve(struct Mechanism *mechanism,
+                          const struct MechInput *inputs,
+                          size_t num_inputs);
+
+/**
+ * x and y of every joint are written to buffer in the order of the joints (2 * capacity doubles)
+ * num_joints : number of joints of the mechanism, written even if capacity is too small so that the caller can allocate the buffer
+ * returns BufferTooSmall and writes no joint if capacity < num_joints
+ *
+ * # Safety
+ *
+ * mechanism must be null or a live handle returned by mechanism_new, num_joints must be null or point to a writable size_t, buffer must be null or point to 2 * capacity writable double.
+ */
+ptrdiff_t mechanism_get_joints(const struct Mechanism *mechanism,
+                               double *buffer,
+                               size_t capacity,
+                               size_t *num_joints);
+
+/**
+ * solve the mechanism for every angle of inputs[input], the handle itself is not changed
+ * angles and errors have num_samples elements, errors[i] is the LinkageSimError of sample i
+ * joints : optional (can be null), x and y of the joints of sample i are written from joints[2 * i * num_joints] in the order of the joints
+ * joints_capacity is counted in joints and must be at least num_samples * number of joints
+ * joints of failed samples are not written
+ * returns error only if the arguments are invalid, failure of each sample is in its error
+ *
+ * # Safety
+ *
+ * mechanism must be null or a live handle returned by mechanism_new, inputs must be null or point to num_inputs readable MechInput,
+ * angles must be null or point to num_samples readable double, errors must be null or point to num_samples writable ptrdiff_t,
+ * joints must be null or point to 2 * joints_capacity writable double.
+ */
+ptrdiff_t mechanism_solve_sweep(const struct Mechanism *mechanism,
+                                const struct MechInput *inputs,
+                                size_t num_inputs,
+                                size_t input,
+                                const double *angles,
+                                size_t num_samples,
+                                ptrdiff_t *errors,
+                                double *joints,
+                                size_t joints_capacity);
+
+/**
+ * # Safety
+ *
+ * mechanism must be null or a handle returned by mechanism_new that is not freed yet, it must not be used after this call.
+ */
+void mechanism_free(struct Mechanism *mechanism);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LINKAGE_SIM_H */
@@ -35,22 +35,32 @@ class Scissor
     private static extern IntPtr create_scissor_dimension_array(ulong size);
     [DllImport("C:/Users/Nakanishi/Documents/linkage_sim/target/release/linkage_sim.dll")]
     private static extern solve_scissor_return solve_from_scissor_dimension_array(IntPtr array, ulong len, double input_radius, double input_theta);
+    [DllImport("C:/Users/Nakanishi/Documents/linkage_sim/target/release/linkage_sim.dll")]
+    private static extern void free_scissor_dimension_array(IntPtr array, ulong len);
+    [DllImport("C:/Users/Nakanishi/Documents/linkage_sim/target/release/linkage_sim.dll")]
+    private static extern void free_solve_scissor_return(solve_scissor_return ret);
+    [DllImport("C:/Users/Nakanishi/Documents/linkage_sim/target/release/linkage_sim.dll")]
+    private static extern IntPtr linkage_sim_last_error_message();
 
     public static ScissorDimension[] create_scissor(ulong size)
     {
-        IntPtr ptr = create_scissor_dimension_array(size);
+        IntPtr array = create_scissor_dimension_array(size);
+        IntPtr ptr = array;
         ScissorDimension[] scissor_dimension = new ScissorDimension[size];
         for (ulong i = 0; i < size; i++)
         {
             scissor_dimension[i] = (ScissorDimension)Marshal.PtrToStructure(ptr, typeof(ScissorDimension));
             ptr += Marshal.SizeOf(typeof(ScissorDimension));
         }
+        free_scissor_dimension_array(array, size);
         return scissor_dimension;
     }
 
     public struct solve_return
     {
+        // LinkageSimError code, 0 is success
         public long error;
+        public string message;
         public line[] lines;
     }
 
@@ -65,6 +75,7 @@ class Scissor
             ptr_inc += Marshal.SizeOf(typeof(ScissorDimension));
         }
         solve_scissor_return ret = solve_from_scissor_dimension_array(ptr, (ulong)scissor_dimension.Length, input_radius, input_theta);
+        free_scissor_dimension_array(ptr, (ulong)scissor_dimension.Length);
         line[] lines = new line[ret.num_lines];
         ptr = ret.lines;
         for (ulong i = 0; i < ret.num_lines; i++)
@@ -72,7 +83,9 @@ class Scissor
             lines[i] = (line)Marshal.PtrToStructure(ptr, typeof(line));
             ptr += Marshal.SizeOf(typeof(line));
         }
-        return new solve_return { error = ret.error, lines = lines };
+        free_solve_scissor_return(ret);
+        string message = ret.error == 0 ? "" : Marshal.PtrToStringUTF8(linkage_sim_last_error_message());
+        return new solve_return { error = ret.error, message = message, lines = lines };
     }
 }
 class test
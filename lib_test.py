@@ -23,7 +23,7 @@ class line(ctypes.Structure) :
                 ("y2", ctypes.c_double)]
 
 class solve_scissor_return(ctypes.Structure) :
-    _fields_ = [("error", ctypes.c_size_t),
+    _fields_ = [("error", ctypes.c_ssize_t),
                 ("num_line", ctypes.c_size_t),
                 ("lines", ctypes.POINTER(line))]
 
@@ -42,16 +42,43 @@ create_scissor_dimension_array.argtypes = [ctypes.c_size_t]
 create_scissor_dimension_array.restype = ctypes.POINTER(ScissorDimension)
 
 get_scissor_dimension_array_element = linkage_sim. get_scissor_dimension_array_element
-get_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t]
-get_scissor_dimension_array_element.restype = ScissorDimension
+get_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_size_t, ctypes.POINTER(ScissorDimension)]
+get_scissor_dimension_array_element.restype = ctypes.c_ssize_t
 
 set_scissor_dimension_array_element = linkage_sim. set_scissor_dimension_array_element
-set_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ScissorDimension]
+set_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_size_t, ScissorDimension]
+set_scissor_dimension_array_element.restype = ctypes.c_ssize_t
 
 solve_from_scissor_dimension_array = linkage_sim.solve_from_scissor_dimension_array
 solve_from_scissor_dimension_array.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_double, ctypes.c_double]
 solve_from_scissor_dimension_array.restype = solve_scissor_return
 
+free_scissor_dimension_array = linkage_sim.free_scissor_dimension_array
+free_scissor_dimension_array.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t]
+
+free_solve_scissor_return = linkage_sim.free_solve_scissor_return
+free_solve_scissor_return.argtypes = [solve_scissor_return]
+
+class scissor_sweep_sample(ctypes.Structure) :
+    _fields_ = [("error", ctypes.c_ssize_t),
+                ("x", ctypes.c_double),
+                ("y", ctypes.c_double)]
+
+scissor_new = linkage_sim.scissor_new
+scissor_new.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t]
+scissor_new.restype = ctypes.c_void_p
+
+scissor_solve_sweep = linkage_sim.scissor_solve_sweep
+scissor_solve_sweep.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_double), ctypes.POINTER(ctypes.c_double), ctypes.c_size_t, ctypes.POINTER(scissor_sweep_sample), ctypes.c_void_p, ctypes.c_size_t]
+scissor_solve_sweep.restype = ctypes.c_ssize_t
+
+scissor_free = linkage_sim.scissor_free
+scissor_free.argtypes = [ctypes.c_void_p]
+
+# message of the last error on this thread, error code of solve_scissor_return is LinkageSimError
+linkage_sim_last_error_message = linkage_sim.linkage_sim_last_error_message
+linkage_sim_last_error_message.restype = ctypes.c_char_p
+
 # Rustの関数を呼び出し
 result = linkage_sim.add(2, 3)
 print(f"Result from Rust: {result}")
@@ -59,14 +86,16 @@ print(f"Result from Rust: {result}")
 array_size = 5
 array = create_scissor_dimension_array(array_size)
 for i in range(array_size):
-    element = get_scissor_dimension_array_element(array, i)
+    element = ScissorDimension()
+    get_scissor_dimension_array_element(array, array_size, i, ctypes.byref(element))
     print(f"element {i} : a={element.a}, b={element.b}, c={element.c}, d={element.d}")
 
 second_scissor = ScissorDimension(1.0, 1.0, 0.6, 0.4)
 print(f"types : array={type(array)}, 1={type(1)}, second_scissor={type(second_scissor)}")
-set_scissor_dimension_array_element(array, 1, second_scissor)
+set_scissor_dimension_array_element(array, array_size, 1, second_scissor)
 for i in range(array_size):
-    element = get_scissor_dimension_array_element(array, i)
+    element = ScissorDimension()
+    get_scissor_dimension_array_element(array, array_size, i, ctypes.byref(element))
     print(f"element {i} : a={element.a}, b={element.b}, c={element.c}, d={element.d}")
 
 solve_return = solve_from_scissor_dimension_array(array, array_size, 0.8, 0.0)
@@ -75,11 +104,20 @@ if solve_return.error == 0:
     for i in range(solve_return.num_line):
         line = solve_return.lines[i]
         plt.plot([line.x1, line.x2], [line.y1, line.y2])
+else:
+    print(f"solve error {solve_return.error} : {linkage_sim_last_error_message().decode('utf-8')}")
+free_solve_scissor_return(solve_return)
+# endpoints of the whole sweep in one call
 inputs = np.linspace(0.8, 0.5, 50)
-for i in inputs:
-    solve_return = solve_from_scissor_dimension_array(array, array_size, i, 0.0)
-    if solve_return.error == 0:
-        plt.scatter(solve_return.lines[9].x2, solve_return.lines[9].y2, color='blue')
+input_radii = (ctypes.c_double * len(inputs))(*inputs)
+input_thetas = (ctypes.c_double * len(inputs))()
+samples = (scissor_sweep_sample * len(inputs))()
+scissor = scissor_new(array, array_size)
+if scissor is not None and scissor_solve_sweep(scissor, input_radii, input_thetas, len(inputs), samples, None, 0) == 0:
+    for sample in samples:
+        if sample.error == 0:
+            plt.scatter(sample.x, sample.y, color='blue')
+scissor_free(scissor)
 plt.show()
 
 # linkage_sim.run_scissor_test()
@@ -87,10 +125,5 @@ plt.show()
 # linkage_sim.run_crosslink_test()
 
 
-# 注意: Rustで確保されたメモリを解放する必要があります
-if sys.platform.startswith('linux'):
-    ctypes.CDLL(None).free(array)
-elif sys.platform.startswith('win'):
-    msvcrt.free(array)
-elif sys.platform.startswith('darwin'):
-    ctypes.CDLL(None).free(array)
\ No newline at end of file
+# 注意: Rustで確保されたメモリはRust側の関数で解放する必要があります
+free_scissor_dimension_array(array, array_size)
\ No newline at end of file
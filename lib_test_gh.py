@@ -16,7 +16,7 @@ class line(ctypes.Structure):
                 ("y2", ctypes.c_double)]
 
 class solve_scissor_return(ctypes.Structure):
-    _fields_ = [("error", ctypes.c_size_t),
+    _fields_ = [("error", ctypes.c_ssize_t),
                 ("num_line", ctypes.c_size_t),
                 ("lines", ctypes.POINTER(line))]
 
@@ -29,16 +29,27 @@ create_scissor_dimension_array.argtypes = [ctypes.c_size_t]
 create_scissor_dimension_array.restype = ctypes.POINTER(ScissorDimension)
 
 get_scissor_dimension_array_element = linkage_sim.get_scissor_dimension_array_element
-get_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t]
-get_scissor_dimension_array_element.restype = ScissorDimension
+get_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_size_t, ctypes.POINTER(ScissorDimension)]
+get_scissor_dimension_array_element.restype = ctypes.c_ssize_t
 
 set_scissor_dimension_array_element = linkage_sim.set_scissor_dimension_array_element
-set_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ScissorDimension]
+set_scissor_dimension_array_element.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_size_t, ScissorDimension]
+set_scissor_dimension_array_element.restype = ctypes.c_ssize_t
 
 solve_from_scissor_dimension_array = linkage_sim.solve_from_scissor_dimension_array
 solve_from_scissor_dimension_array.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t, ctypes.c_double, ctypes.c_double]
 solve_from_scissor_dimension_array.restype = solve_scissor_return
 
+free_scissor_dimension_array = linkage_sim.free_scissor_dimension_array
+free_scissor_dimension_array.argtypes = [ctypes.POINTER(ScissorDimension), ctypes.c_size_t]
+
+free_solve_scissor_return = linkage_sim.free_solve_scissor_return
+free_solve_scissor_return.argtypes = [solve_scissor_return]
+
+# message of the last error on this thread, error code of solve_scissor_return is LinkageSimError
+linkage_sim_last_error_message = linkage_sim.linkage_sim_last_error_message
+linkage_sim_last_error_message.restype = ctypes.c_char_p
+
 # call Rust function
 result = linkage_sim.add(2, 3)
 print "Result from Rust: {}".format(result)
@@ -46,14 +57,16 @@ print "Result from Rust: {}".format(result)
 array_size = 5
 array = create_scissor_dimension_array(array_size)
 for i in range(array_size):
-    element = get_scissor_dimension_array_element(array, i)
+    element = ScissorDimension()
+    get_scissor_dimension_array_element(array, array_size, i, ctypes.byref(element))
     print "element {} : a={}, b={}, c={}, d={}".format(i, element.a, element.b, element.c, element.d)
 
 second_scissor = ScissorDimension(1.0, 1.0, 0.6, 0.4)
 print "types : array={}, 1={}, second_scissor={}".format(type(array), type(1), type(second_scissor))
-set_scissor_dimension_array_element(array, 1, second_scissor)
+set_scissor_dimension_array_element(array, array_size, 1, second_scissor)
 for i in range(array_size):
-    element = get_scissor_dimension_array_element(array, i)
+    element = ScissorDimension()
+    get_scissor_dimension_array_element(array, array_size, i, ctypes.byref(element))
     print "element {} : a={}, b={}, c={}, d={}".format(i, element.a, element.b, element.c, element.d)
 
 solve_return = solve_from_scissor_dimension_array(array, array_size, 0.8, 0.0)
@@ -62,6 +75,9 @@ if solve_return.error == 0:
     for i in range(solve_return.num_line):
         line = solve_return.lines[i]
         print ([line.x1, line.x2], [line.y1, line.y2])
+else:
+    print "solve error {} : {}".format(solve_return.error, linkage_sim_last_error_message().decode('utf-8'))
+free_solve_scissor_return(solve_return)
 # inputs = np.linspace(0.8, 0.5, 50)
 # for i in inputs:
 #     solve_return = solve_from_scissor_dimension_array(array, array_size, i, 0.0)
@@ -70,9 +86,4 @@ if solve_return.error == 0:
 
 
 # memory free
-if sys.platform.startswith('linux'):
-    ctypes.CDLL(None).free(array)
-elif sys.platform.startswith('win'):
-    msvcrt.free(array)
-elif sys.platform.startswith('darwin'):
-    ctypes.CDLL(None).free(array)
+free_scissor_dimension_array(array, array_size)
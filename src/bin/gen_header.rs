@@ -0,0 +1,33 @@
+use std::process::ExitCode;
+
+// generate include/linkage_sim.h from the extern "C" functions and #[repr(C)] types
+// header is checked in so that C clients do not need to build the crate to get it
+// --check : do not write the header, fail if the checked in header differs from the generated one
+fn main() -> ExitCode {
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    let crate_dir = env!("CARGO_MANIFEST_DIR");
+    let config_path = format!("{}/cbindgen.toml", crate_dir);
+    let header_path = format!("{}/include/linkage_sim.h", crate_dir);
+    let config = cbindgen::Config::from_file(&config_path)
+        .unwrap_or_else(|error| panic!("cannot read cbindgen config {} : {}", config_path, error));
+    let bindings = match cbindgen::generate_with_config(crate_dir, config){
+        Ok(bindings) => bindings,
+        Err(error) => {
+            eprintln!("C header is not generated : {}", error);
+            return ExitCode::FAILURE;
+        },
+    };
+    if check {
+        let mut generated = Vec::new();
+        bindings.write(&mut generated);
+        let checked_in = std::fs::read(&header_path)
+            .unwrap_or_else(|error| panic!("cannot read {} : {}", header_path, error));
+        if generated != checked_in {
+            eprintln!("{} is stale, run cargo run --features capi --bin gen-header", header_path);
+            return ExitCode::FAILURE;
+        }
+    } else {
+        bindings.write_to_file(&header_path);
+    }
+    ExitCode::SUCCESS
+}
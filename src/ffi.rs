@@ -0,0 +1,108 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+// error codes returned across the C ABI, 0 is success
+// error message of the last failed call is given by linkage_sim_last_error_message
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkageSimError{
+    Ok = 0,
+    // error that has no specific code (1 was returned for every error before codes were defined)
+    Unknown = 1,
+    NullPointer = 2,
+    NoElement = 3,
+    InvalidDimension = 4,
+    // input is not finite
+    InvalidInput = 5,
+    // loop closure failed, the mechanism cannot be assembled at the input
+    CannotAssemble = 6,
+    Panic = 7,
+    // index is not less than the length of the array
+    IndexOutOfRange = 8,
+    // result is requested before the mechanism is solved
+    NotSolved = 9,
+    // buffer given by the caller is shorter than the result
+    BufferTooSmall = 10,
+}
+
+// error with its code and message, converted to the code at the C ABI
+#[derive(Debug)]
+pub(crate) struct FfiError{
+    pub code: LinkageSimError,
+    pub message: String,
+}
+
+impl FfiError{
+    pub fn new(code: LinkageSimError, message: impl Into<String>) -> Self{
+        FfiError{code, message: message.into()}
+    }
+    // store the message for linkage_sim_last_error_message and return the code
+    pub fn into_code(self) -> isize{
+        set_last_error(&self.message);
+        self.code as isize
+    }
+}
+
+thread_local! {
+    static LAST_ERROR_MESSAGE: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(message: &str){
+    // interior nul cannot be passed as C string
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR_MESSAGE.with(|last| *last.borrow_mut() = message);
+}
+
+// message of the last error on the calling thread as UTF-8 C string, empty if no error occurred
+// successful calls do not clear the message, so read it only when a call returned an error
+// pointer is owned by the library, do not free it
+// pointer is valid only until the next call of any function of this library on the same thread, copy the message before that
+#[no_mangle]
+pub extern "C" fn linkage_sim_last_error_message() -> *const c_char{
+    catch_panic(c"".as_ptr(), || LAST_ERROR_MESSAGE.with(|last| last.borrow().as_ptr()))
+}
+
+// every extern "C" function runs its body through catch_panic
+// panic must not unwind into the host process (Rhino/Grasshopper, Python, C#), it aborts the host
+// on_panic : value returned to the caller when the body panics, message of the panic is stored as the last error
+pub(crate) fn catch_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T{
+    // pointers and buffers are not touched after the panic, so the body is treated as unwind safe
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        set_last_error(&format!("panic : {}", message));
+        on_panic
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn last_error_message() -> String {
+        unsafe { CStr::from_ptr(linkage_sim_last_error_message()) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn panic_returns_on_panic_and_sets_message() {
+        assert_eq!(last_error_message(), "");
+        assert_eq!(catch_panic(7, || -> i32 {panic!("static message")}), 7);
+        assert_eq!(last_error_message(), "panic : static message");
+        let index = 3;
+        assert_eq!(catch_panic(-1, || -> i32 {panic!("formatted message {}", index)}), -1);
+        assert_eq!(last_error_message(), "panic : formatted message 3");
+    }
+
+    #[test]
+    fn success_keeps_the_last_error_message() {
+        assert_eq!(FfiError::new(LinkageSimError::NotSolved, "not solved\0yet").into_code(), LinkageSimError::NotSolved as isize);
+        // interior nul is replaced so that the whole message is readable
+        assert_eq!(last_error_message(), "not solved yet");
+        assert_eq!(catch_panic(0, || 1), 1);
+        assert_eq!(last_error_message(), "not solved yet");
+    }
+}
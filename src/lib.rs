@@ -1,21 +1,47 @@
+mod ffi;
 mod mech_solver;
 mod scissor_solver;
+mod sim;
+#[cfg(feature = "python")]
+mod python;
 use std::f64::consts::PI;
-use num_traits::Float;
 use plotters::prelude::*;
 
-use mech_solver::triangle_solver::*;
-
 use mech_solver::triangle_solver::variable_vector::VariableF;
 
-use crate::mech_solver::triangle_solver::variable_vector::{Variable, VariableFRecVec2, VariableFPolVec2};
+use crate::mech_solver::triangle_solver::variable_vector::{VariableFRecVec2, VariableFPolVec2};
 
-pub use scissor_solver::{ScissorDimension, create_scissor_dimension_array, get_scissor_dimension_array_element, set_scissor_dimension_array_element};
+pub use ffi::{LinkageSimError, linkage_sim_last_error_message};
+pub use mech_solver::triangle_solver::{Triangle, triangle_side, triangle_description, solve_triangle_return, solve_triangle};
+pub use scissor_solver::{Scissor, ScissorJoint, ScissorJointRole, JOINTS_PER_ELEMENT, AngulatedScissorDimension, ScissorSolveReport, ScissorLimitPosition, ScissorNewErr, ScissorDimensionRule, InvalidScissorDimension, ScissorDimension, create_scissor_dimension_array, free_scissor_dimension_array, get_scissor_dimension_array_element, set_scissor_dimension_array_element, scissor_new, scissor_solve, scissor_get_endpoint, scissor_get_joints, scissor_solve_sweep, scissor_sweep_sample, scissor_free};
+pub use mech_solver::limit_position::SweepRange;
+pub use mech_solver::synthesis::{FourBarDimension, FourBarBranch, FourBarPosition, FourBarLimitPosition, CouplerPosition, MotionSynthesisResult, PathSynthesisResult, two_position_motion, three_position_motion, path_generation};
+pub use scissor_solver::polar::{PolarScissor, polar_scissor_dimensions};
+pub use scissor_solver::curve_synthesis::{CurveSynthesisResult, fit_to_curve};
+pub use scissor_solver::actuator::{ScissorJointRef, LinearActuator, ActuatorStroke};
+pub use scissor_solver::boundary::{ScissorStartSupport, ScissorEndSupport, BoundaryErr};
+pub use scissor_solver::network::{ScissorUnit, ScissorNetwork, NetworkErr};
+pub use scissor_solver::collision::{ScissorBar, ScissorContact};
+pub use scissor_solver::load::{ScissorBarForce, ScissorLoadState};
+pub use scissor_solver::spatial::{SpatialScissorDimension, SpatialScissorJoint, SpatialScissor};
+pub use sim::{Mechanism, MechInput, SolveErr, PinJoint, SolveHint, Linkage, MechanismSolveReport, mechanism_new, mechanism_fix_joint, mechanism_add_hint, mechanism_solve, mechanism_get_joints, mechanism_solve_sweep, mechanism_free};
+pub use sim::collision::MechanismContact;
 
+// returns 0 on success and 1 if drawing fails or panics
 #[no_mangle]
-pub extern "C" fn run_scissor_test(){
+pub extern "C" fn run_scissor_test() -> isize{
+    ffi::catch_panic(1, || match draw_scissor_test(){
+        Ok(()) => 0,
+        Err(error) => {
+            println!("scissor test failed : {}", error);
+            1
+        },
+    })
+}
+
+fn draw_scissor_test() -> Result<(), Box<dyn std::error::Error>>{
     let mut backend = BitMapBackend::new("scissor_test.png", (480, 480));
-    backend.draw_rect((0, 0), (480, 480), &WHITE, true).unwrap();
+    backend.draw_rect((0, 0), (480, 480), &WHITE, true)?;
     let origin_scissor = scissor_solver::Scissor::new(vec![
         scissor_solver::ScissorDimension{a: 1.0, b: 1.0, c: 0.6, d: 0.4},
         scissor_solver::ScissorDimension{a: 1.0, b: 1.0, c: 0.6, d: 0.4},
@@ -25,28 +51,40 @@ pub extern "C" fn run_scissor_test(){
         scissor_solver::ScissorDimension{a: 1.0, b: 1.0, c: 0.5, d: 0.5},
         scissor_solver::ScissorDimension{a: 1.0, b: 1.0, c: 0.5, d: 0.5},
         scissor_solver::ScissorDimension{a: 1.0, b: 1.0, c: 0.5, d: 0.5},
-    ]);
+    ])?;
     let mut scissor = origin_scissor.clone();
-    scissor.solve(VariableFPolVec2::from(0.9, 0.0)).unwrap();
-    scissor.draw(&mut backend, 100.0, &BLACK).unwrap();
+    scissor.solve(VariableFPolVec2::from(0.9, 0.0))?;
+    scissor.draw(&mut backend, 100.0, &BLACK)?;
     for i in 0..100{
         let mut scissor = origin_scissor.clone();
-        scissor.solve(VariableFPolVec2::from(0.998 - 0.001*i as f64, 0.0)).unwrap();
-        let (x,y) = scissor.get_endpoint().unwrap();
-        backend.draw_pixel(((x*100.)as i32, backend.get_size().1 as i32 - (y*100.)as i32), BLUE.to_backend_color()).unwrap();
+        scissor.solve(VariableFPolVec2::from(0.998 - 0.001*i as f64, 0.0))?;
+        let (x,y) = scissor.get_endpoint()?;
+        backend.draw_pixel(((x*100.)as i32, backend.get_size().1 as i32 - (y*100.)as i32), BLUE.to_backend_color())?;
     }
+    Ok(())
 }
 
+// returns 0 on success and 1 if drawing fails or panics
 #[no_mangle]
-pub extern "C" fn run_triangle_test(){
+pub extern "C" fn run_triangle_test() -> isize{
+    ffi::catch_panic(1, || match draw_triangle_test(){
+        Ok(()) => 0,
+        Err(error) => {
+            println!("triangle test failed : {}", error);
+            1
+        },
+    })
+}
+
+fn draw_triangle_test() -> Result<(), Box<dyn std::error::Error>>{
     let mut backend = BitMapBackend::new("triangle_test.png", (640, 480));
-    backend.draw_rect((0, 0), (640, 480), &WHITE, true).unwrap();
+    backend.draw_rect((0, 0), (640, 480), &WHITE, true)?;
     let original_triangle = Triangle::from_len([1.0, 1.0, 1.0]);
     for i in 0..5{
         // triangle.c.theta = VariableF::Fixed(PI/2.0);
-        let mut triangle = original_triangle.clone();
+        let mut triangle = original_triangle;
         triangle.a.theta = VariableF::Fixed((i as f64) * 0.4* PI);
-        triangle = triangle.solve().unwrap();
+        triangle = triangle.solve()?;
         println!("a: {:?}", triangle.a);
         println!("b: {:?}", triangle.b);
         println!("c: {:?}", triangle.c);
@@ -66,19 +104,31 @@ pub extern "C" fn run_triangle_test(){
                 let by = -(by * 200.0) as i32;
                 let cx = (cx * 200.0) as i32;
                 let cy = -(cy * 200.0) as i32;
-            backend.draw_line((originx, originy), (originx+ax,originy+ay), &RED).unwrap();
-            backend.draw_line((originx+ax,originy+ay), (originx+ax+bx,originy+ay+by), &GREEN).unwrap();
-            backend.draw_line((originx+ax+bx,originy+ay+by), (originx+ax+bx+cx,originy+ay+by+cy), &BLUE).unwrap();
+            backend.draw_line((originx, originy), (originx+ax,originy+ay), &RED)?;
+            backend.draw_line((originx+ax,originy+ay), (originx+ax+bx,originy+ay+by), &GREEN)?;
+            backend.draw_line((originx+ax+bx,originy+ay+by), (originx+ax+bx+cx,originy+ay+by+cy), &BLUE)?;
         }else {
             println!("not fixed");
         }
     }
+    Ok(())
 }
 
+// returns 0 on success and 1 if drawing fails or panics
 #[no_mangle]
-pub extern "C" fn run_crosslink_test(){
+pub extern "C" fn run_crosslink_test() -> isize{
+    ffi::catch_panic(1, || match draw_crosslink_test(){
+        Ok(()) => 0,
+        Err(error) => {
+            println!("crosslink test failed : {}", error);
+            1
+        },
+    })
+}
+
+fn draw_crosslink_test() -> Result<(), Box<dyn std::error::Error>>{
     let mut backend = BitMapBackend::new("crosslink_test.png", (640, 480));
-    backend.draw_rect((0, 0), (640, 480), &WHITE, true).unwrap();
+    backend.draw_rect((0, 0), (640, 480), &WHITE, true)?;
     let dx_zero = 600.0;
     let dy_zero = 20.0;
     let dx_scale = 300.0;
@@ -95,18 +145,18 @@ pub extern "C" fn run_crosslink_test(){
     let mut tr2 = Triangle::from_len([e3, e4, i1]);
     tr1.a.theta = VariableF::Fixed(-PI/2.0);
     for i in 0..2{
-        if(i == 0){
+        if i == 0 {
             tr1.c.radius = VariableF::Fixed(i1);
             tr2.c.radius = VariableF::Fixed(i1);
         }else{
             tr1.c.radius = VariableF::Fixed(i2);
             tr2.c.radius = VariableF::Fixed(i2);
         }
-        let mut tr1 = tr1.clone();
-        let mut tr2 = tr2.clone();
-        tr1 = tr1.solve().unwrap();
+        let mut tr1 = tr1;
+        let mut tr2 = tr2;
+        tr1 = tr1.solve()?;
         tr2.c.theta = tr1.c.theta;
-        tr2 = tr2.solve().unwrap();
+        tr2 = tr2.solve()?;
         //draw e1~4 black
         if let (
             VariableFRecVec2{x: VariableF::<f64>::Fixed(e1x), y: VariableF::<f64>::Fixed(e1y)}, 
@@ -119,28 +169,28 @@ pub extern "C" fn run_crosslink_test(){
                 ((dx_zero) as i32, (dy_zero) as i32), 
                 ((e1x * dx_scale + dx_zero) as i32, (e1y * dy_scale + dy_zero) as i32), 
                 &BLACK
-            ).unwrap();
+            )?;
             backend.draw_line(
                 ((e1x * dx_scale + dx_zero) as i32, (e1y * dy_scale + dy_zero) as i32), 
                 ((e1x * dx_scale + dx_zero + e2x * dx_scale) as i32, (e1y * dy_scale + dy_zero + e2y * dy_scale) as i32), 
                 &BLACK
-            ).unwrap();
+            )?;
             backend.draw_line(
                 ((dx_zero) as i32, (dy_zero) as i32), 
                 ((e3x * dx_scale + dx_zero) as i32, (e3y * dy_scale + dy_zero) as i32), 
                 &BLACK
-            ).unwrap();
+            )?;
             backend.draw_line(
                 ((e3x * dx_scale + dx_zero) as i32, (e3y * dy_scale + dy_zero) as i32), 
                 ((e3x * dx_scale + dx_zero + e4x * dx_scale) as i32, (e3y * dy_scale + dy_zero + e4y * dy_scale) as i32), 
                 &BLACK
-            ).unwrap();
+            )?;
             //draw i1 BLUE
             backend.draw_line(
                 ((e1x * dx_scale + dx_zero + e2x * dx_scale) as i32, (e1y * dy_scale + dy_zero + e2y * dy_scale) as i32), 
                 ((i1x * dx_scale + e1x * dx_scale + e2x * dx_scale + dx_zero) as i32, (i1y * dy_scale + e1y * dy_scale + e2y * dy_scale + dy_zero) as i32), 
                 &BLUE
-            ).unwrap();
+            )?;
         }else {
             println!("not fixed");
         }
@@ -148,14 +198,14 @@ pub extern "C" fn run_crosslink_test(){
     }
     //draw path in red
     for i in 0..step_num {
-        let mut tr1 = tr1.clone();
-        let mut tr2 = tr2.clone();
+        let mut tr1 = tr1;
+        let mut tr2 = tr2;
         tr1.a.theta = VariableF::Fixed(-PI/2.0);
         tr1.c.radius = VariableF::Fixed(i1 + step * (i as f64));
         tr2.c.radius = VariableF::Fixed(i1 + step * (i as f64));
-        tr1 = tr1.solve().unwrap();
+        tr1 = tr1.solve()?;
         tr2.c.theta = tr1.c.theta;
-        tr2 = tr2.solve().unwrap();
+        tr2 = tr2.solve()?;
         if let (
             VariableFRecVec2{x: VariableF::<f64>::Fixed(e1x), y: VariableF::<f64>::Fixed(e1y)},
             VariableFRecVec2{x: VariableF::<f64>::Fixed(e2x), y: VariableF::<f64>::Fixed(e2y)},
@@ -165,14 +215,15 @@ pub extern "C" fn run_crosslink_test(){
                 ((e1x * dx_scale + dx_zero + e2x * dx_scale) as i32, (e1y * dy_scale + dy_zero + e2y * dy_scale) as i32), 
                 ((e3x * dx_scale + dx_zero) as i32, (e3y * dy_scale + dy_zero) as i32), 
                 &RED
-            ).unwrap();
+            )?;
         }else {
             println!("not fixed");
         }
     }
+    Ok(())
 }
 
 #[no_mangle]
 pub extern "C" fn add(a: i32, b: i32) -> i32 {
-    a + b
+    ffi::catch_panic(0, || a.wrapping_add(b))
 }
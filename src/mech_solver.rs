@@ -1 +1,5 @@
 pub mod triangle_solver;
+pub mod limit_position;
+pub mod optimize;
+pub mod synthesis;
+pub mod linear_system;
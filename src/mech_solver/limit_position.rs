@@ -0,0 +1,82 @@
+// limit position is the input value where the mechanism cannot be assembled any more
+// can_assemble is called with input value and returns whether the mechanism can be solved
+
+// input range of a sweep, from start to end by steps, and tolerance of the bisection
+#[derive(Debug, Clone, Copy)]
+pub struct SweepRange{
+    pub start: f64,
+    pub end: f64,
+    pub steps: usize,
+    pub tolerance: f64,
+}
+
+// sweep from start to end by steps and return the first pair (assembled, lost)
+pub fn bracket_limit<F>(start: f64, end: f64, steps: usize, mut can_assemble: F) -> Option<(f64, f64)>
+    where F: FnMut(f64) -> bool
+{
+    if steps == 0 || !can_assemble(start) {return None;}
+    let step = (end - start) / steps as f64;
+    let mut assembled = start;
+    for i in 1..=steps {
+        let input = start + step * i as f64;
+        if !can_assemble(input) {return Some((assembled, input));}
+        assembled = input;
+    }
+    None
+}
+
+// bisect between assembled and lost until the interval is smaller than tolerance
+// return value is the narrowed pair (assembled, lost)
+pub fn bisect_limit<F>(mut assembled: f64, mut lost: f64, tolerance: f64, mut can_assemble: F) -> (f64, f64)
+    where F: FnMut(f64) -> bool
+{
+    while (lost - assembled).abs() > tolerance {
+        let middle = (assembled + lost) / 2.0;
+        if middle == assembled || middle == lost {break;}
+        if can_assemble(middle) {
+            assembled = middle;
+        } else {
+            lost = middle;
+        }
+    }
+    (assembled, lost)
+}
+
+// bracket_limit and bisect_limit in one call
+pub fn find_limit<F>(start: f64, end: f64, steps: usize, tolerance: f64, mut can_assemble: F) -> Option<(f64, f64)>
+    where F: FnMut(f64) -> bool
+{
+    let (assembled, lost) = bracket_limit(start, end, steps, &mut can_assemble)?;
+    Some(bisect_limit(assembled, lost, tolerance, can_assemble))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // assembled below 0.3
+    fn can_assemble(input: f64) -> bool {
+        input < 0.3
+    }
+
+    #[test]
+    fn limit_is_bracketed_and_bisected() {
+        let (assembled, lost) = bracket_limit(0.0, 1.0, 10, can_assemble).unwrap();
+        assert!(can_assemble(assembled) && !can_assemble(lost));
+        assert!((lost - assembled - 0.1).abs() < 1e-12);
+        let (assembled, lost) = find_limit(0.0, 1.0, 10, 1e-12, can_assemble).unwrap();
+        assert!(can_assemble(assembled) && !can_assemble(lost));
+        assert!((assembled - 0.3).abs() < 1e-12 && (lost - 0.3).abs() < 1e-12);
+        // sweep downward
+        let (assembled, lost) = find_limit(0.0, -1.0, 10, 1e-12, |input| input > -0.45).unwrap();
+        assert!((assembled + 0.45).abs() < 1e-12 && lost <= -0.45);
+    }
+
+    #[test]
+    fn no_limit_is_none() {
+        assert!(bracket_limit(0.0, 0.2, 10, can_assemble).is_none());
+        assert!(bracket_limit(0.5, 1.0, 10, can_assemble).is_none());
+        assert!(bracket_limit(0.0, 1.0, 0, can_assemble).is_none());
+        assert!(find_limit(0.0, 0.2, 10, 1e-12, can_assemble).is_none());
+    }
+}
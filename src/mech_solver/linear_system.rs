@@ -0,0 +1,62 @@
+// solve a x = b by Gaussian elimination with partial pivoting
+// return None if a is singular
+pub fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    let scale = a.iter().flat_map(|row| row.iter()).fold(0.0f64, |max, value| max.max(value.abs()));
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&i, &j| a[i][column].abs().partial_cmp(&a[j][column].abs()).unwrap_or(std::cmp::Ordering::Equal))?;
+        if a[pivot][column].abs() <= scale * 1e-12 {return None;}
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let (upper, lower) = a.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[column] / pivot_row[column];
+            if factor == 0.0 {continue;}
+            for (k, value) in row.iter_mut().enumerate().skip(column) {
+                *value -= factor * pivot_row[k];
+            }
+            b[column + 1 + offset] -= factor * b[column];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(x: &[f64], expected: &[f64]) {
+        assert_eq!(x.len(), expected.len());
+        for (x, expected) in x.iter().zip(expected) {
+            assert!((x - expected).abs() < 1e-12, "{:?} != {:?}", x, expected);
+        }
+    }
+
+    #[test]
+    fn solve_2x2() {
+        // 2x + y = 5, x - y = 1
+        let x = solve_linear(vec![vec![2.0, 1.0], vec![1.0, -1.0]], vec![5.0, 1.0]).unwrap();
+        assert_close(&x, &[2.0, 1.0]);
+    }
+
+    #[test]
+    fn solve_needs_pivoting() {
+        // first pivot is 0, rows must be swapped
+        let a = vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![3.0, 0.0, 1.0]];
+        let x = solve_linear(a, vec![7.0, 3.0, 6.0]).unwrap();
+        assert_close(&x, &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn singular_returns_none() {
+        // second row is twice the first
+        assert!(solve_linear(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![3.0, 6.0]).is_none());
+        assert!(solve_linear(vec![vec![0.0, 0.0], vec![0.0, 0.0]], vec![0.0, 0.0]).is_none());
+    }
+}
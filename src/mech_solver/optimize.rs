@@ -0,0 +1,175 @@
+// Nelder-Mead simplex method for minimizing a function without gradient
+// x0 : initial point
+// step : initial size of the simplex for each coordinate
+// return (best point, value at best point)
+pub fn nelder_mead<F>(mut f: F, x0: &[f64], step: &[f64], max_iter: usize, tolerance: f64) -> (Vec<f64>, f64)
+    where F: FnMut(&[f64]) -> f64
+{
+    let n = x0.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((x0.to_vec(), f(x0)));
+    for i in 0..n {
+        let mut x = x0.to_vec();
+        x[i] += step[i];
+        let value = f(&x);
+        simplex.push((x, value));
+    }
+    for _ in 0..max_iter {
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if (simplex[n].1 - simplex[0].1).abs() <= tolerance {break;}
+        // centroid of all points except the worst
+        let mut centroid = vec![0.0; n];
+        for (x, _) in simplex.iter().take(n) {
+            for i in 0..n {centroid[i] += x[i] / n as f64;}
+        }
+        let along = |t: f64, worst: &[f64]| -> Vec<f64> {
+            (0..n).map(|i| centroid[i] + t * (worst[i] - centroid[i])).collect()
+        };
+        let reflected = along(-1.0, &simplex[n].0);
+        let reflected_value = f(&reflected);
+        if reflected_value < simplex[0].1 {
+            let expanded = along(-2.0, &simplex[n].0);
+            let expanded_value = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value {(expanded, expanded_value)} else {(reflected, reflected_value)};
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = along(0.5, &simplex[n].0);
+            let contracted_value = f(&contracted);
+            if contracted_value < simplex[n].1 {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                // shrink toward the best point
+                let best = simplex[0].0.clone();
+                for (x, value) in simplex.iter_mut().skip(1) {
+                    for i in 0..n {x[i] = best[i] + 0.5 * (x[i] - best[i]);}
+                    *value = f(x);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    simplex.swap_remove(0)
+}
+
+// find every x where f(x) = 0 between start and end, in the order from start
+// sweep by steps to find sign changes and bisect each of them until the interval is smaller than tolerance
+// roots closer to each other than (end - start) / steps can be missed
+// f returns None where it cannot be evaluated, such points and NaN are skipped
+pub fn find_roots<F>(f: F, start: f64, end: f64, steps: usize, tolerance: f64) -> Vec<f64>
+    where F: FnMut(f64) -> Option<f64>
+{
+    let mut roots = Vec::new();
+    sweep_roots(f, start, end, steps, tolerance, |root| {
+        roots.push(root);
+        true
+    });
+    roots
+}
+
+// first root of find_roots, the sweep stops there
+pub fn find_root<F>(f: F, start: f64, end: f64, steps: usize, tolerance: f64) -> Option<f64>
+    where F: FnMut(f64) -> Option<f64>
+{
+    let mut first = None;
+    sweep_roots(f, start, end, steps, tolerance, |root| {
+        first = Some(root);
+        false
+    });
+    first
+}
+
+// on_root is called for each root and returns whether the sweep continues
+fn sweep_roots<F, R>(mut f: F, start: f64, end: f64, steps: usize, tolerance: f64, mut on_root: R)
+    where F: FnMut(f64) -> Option<f64>, R: FnMut(f64) -> bool
+{
+    if steps == 0 {return;}
+    let step = (end - start) / steps as f64;
+    let mut previous: Option<(f64, f64)> = None;
+    for i in 0..=steps {
+        let x = start + step * i as f64;
+        let value = match f(x) {
+            Some(value) if !value.is_nan() => value,
+            _ => {previous = None; continue;}
+        };
+        if value == 0.0 {
+            // sign of the next sample is compared with nothing, so the same root is not found twice
+            previous = None;
+            if on_root(x) {continue;} else {return;}
+        }
+        if let Some((mut low, low_value)) = previous {
+            if low_value.signum() != value.signum() {
+                let mut high = x;
+                while (high - low).abs() > tolerance {
+                    let middle = (low + high) / 2.0;
+                    match f(middle) {
+                        Some(middle_value) if middle_value.is_nan() => break,
+                        Some(middle_value) if middle_value.signum() == low_value.signum() => low = middle,
+                        Some(_) => high = middle,
+                        None => break,
+                    }
+                }
+                if !on_root((low + high) / 2.0) {return;}
+            }
+        }
+        previous = Some((x, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nelder_mead_finds_minimum_of_quadratic() {
+        let (x, value) = nelder_mead(|x| (x[0] - 1.0).powi(2) + 2.0 * (x[1] + 3.0).powi(2), &[0.0, 0.0], &[0.5, 0.5], 1000, 1e-20);
+        assert!((x[0] - 1.0).abs() < 1e-6 && (x[1] + 3.0).abs() < 1e-6, "{:?}", x);
+        assert!(value < 1e-12);
+    }
+
+    #[test]
+    fn nelder_mead_finds_minimum_of_rosenbrock() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+        let (x, _) = nelder_mead(rosenbrock, &[-1.2, 1.0], &[0.1, 0.1], 5000, 1e-24);
+        assert!((x[0] - 1.0).abs() < 1e-4 && (x[1] - 1.0).abs() < 1e-4, "{:?}", x);
+    }
+
+    #[test]
+    fn find_root_of_polynomial() {
+        let root = find_root(|x| Some(x * x - 2.0), 0.0, 3.0, 7, 1e-12).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-11);
+    }
+
+    #[test]
+    fn find_root_returns_first_root_and_find_roots_returns_all() {
+        let f = |x: f64| Some(x.sin());
+        let root = find_root(f, 1.0, 10.0, 90, 1e-12).unwrap();
+        assert!((root - std::f64::consts::PI).abs() < 1e-11);
+        let roots = find_roots(f, 1.0, 10.0, 90, 1e-12);
+        assert_eq!(roots.len(), 3);
+        for (k, root) in roots.iter().enumerate() {
+            assert!((root - (k + 1) as f64 * std::f64::consts::PI).abs() < 1e-11, "{:?}", roots);
+        }
+    }
+
+    #[test]
+    fn exact_root_on_sample_is_found_once() {
+        assert_eq!(find_roots(|x| Some(x - 1.0), 0.0, 2.0, 4, 1e-12), vec![1.0]);
+    }
+
+    #[test]
+    fn root_is_not_bracketed_across_unevaluable_points() {
+        // f cannot be evaluated between the sign change, so no root is reported
+        let f = |x: f64| if (0.9..1.1).contains(&x) {None} else {Some(x - 1.0)};
+        assert_eq!(find_root(f, 0.0, 2.0, 4, 1e-12), None);
+        assert_eq!(find_root(f, 0.0, 2.0, 40, 1e-12), None);
+    }
+
+    #[test]
+    fn no_bracket_returns_none() {
+        assert_eq!(find_root(|x| Some(x * x + 1.0), -3.0, 3.0, 100, 1e-12), None);
+        assert!(find_roots(|x| Some(x * x + 1.0), -3.0, 3.0, 100, 1e-12).is_empty());
+        assert_eq!(find_root(|_| Some(f64::NAN), -3.0, 3.0, 100, 1e-12), None);
+        assert_eq!(find_root(Some, -1.0, 1.0, 0, 1e-12), None);
+    }
+}
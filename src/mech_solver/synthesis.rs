@@ -0,0 +1,520 @@
+use std::io::{Error, ErrorKind};
+
+use cgmath::{Vector2, Point2};
+
+use super::optimize;
+use super::limit_position::{self, SweepRange};
+use crate::sim::{Mechanism, Linkage, PinJoint, SolveHint};
+use super::triangle_solver::{Triangle, variable_vector::{VariableF, VariableFPolVec2, VariableFRecVec2}};
+
+// assembly mode of the four-bar, B has two positions for one crank angle
+// Left : B is on the left of the diagonal from A to ground_b, Right : B is on the right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FourBarBranch{
+    Left,
+    Right,
+}
+
+// cross product of the diagonal (A to ground_b) and AB, positive if B is on the left
+fn branch_side(a: (f64, f64), b: (f64, f64), ground_b: (f64, f64)) -> f64{
+    (ground_b.0 - a.0) * (b.1 - a.1) - (ground_b.1 - a.1) * (b.0 - a.0)
+}
+
+// FourBarDimension is a four-bar linkage driven by the crank
+// ground_a : ground pivot of the crank
+// ground_b : ground pivot of the rocker
+// crank : length from ground_a to moving pivot A
+// coupler : length from moving pivot A to moving pivot B
+// rocker : length from ground_b to moving pivot B
+// coupler_point : coupler point relative to A, x along AB and y to the left of AB
+#[derive(Debug, Clone, Copy)]
+pub struct FourBarDimension{
+    pub ground_a: (f64, f64),
+    pub ground_b: (f64, f64),
+    pub crank: f64,
+    pub coupler: f64,
+    pub rocker: f64,
+    pub coupler_point: (f64, f64),
+}
+
+// FourBarPosition is solved four-bar at one crank angle
+#[derive(Debug, Clone, Copy)]
+pub struct FourBarPosition{
+    pub a: (f64, f64),
+    pub b: (f64, f64),
+    pub coupler_point: (f64, f64),
+    pub coupler_theta: f64,
+}
+
+// CouplerPosition is a precision position of the coupler
+// (x, y) : position of the coupler point
+// theta : angle of the coupler
+#[derive(Debug, Clone, Copy)]
+pub struct CouplerPosition{
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64,
+}
+
+// MotionSynthesisResult is the result of two_position_motion and three_position_motion
+// branch : assembly mode that passes every precision position
+// crank_angles : crank angle at each precision position, solve(crank_angles[i], branch) puts the coupler at positions[i]
+#[derive(Debug, Clone)]
+pub struct MotionSynthesisResult{
+    pub dimension: FourBarDimension,
+    pub branch: FourBarBranch,
+    pub crank_angles: Vec<f64>,
+}
+
+// PathSynthesisResult is the result of path_generation
+// residual : root mean square distance from target points to the coupler curve
+#[derive(Debug, Clone, Copy)]
+pub struct PathSynthesisResult{
+    pub dimension: FourBarDimension,
+    pub residual: f64,
+}
+
+// FourBarLimitPosition is where the four-bar cannot be assembled any more
+// crank_angle : the last crank angle that can be assembled
+// triangle_index : index of the triangle in to_triangles that became degenerate
+// stretched : true if coupler and rocker are fully opened (diagonal > coupler + rocker), false if fully folded
+#[derive(Debug, Clone, Copy)]
+pub struct FourBarLimitPosition{
+    pub crank_angle: f64,
+    pub triangle_index: usize,
+    pub stretched: bool,
+}
+
+fn fixed_rec(vec: VariableFPolVec2) -> Result<(f64, f64), Error>{
+    if let VariableFRecVec2{x: VariableF::Fixed(x), y: VariableF::Fixed(y)} = vec.to_rec(){
+        Ok((x, y))
+    }else{
+        Err(Error::other("solve error : vector is not fixed"))
+    }
+}
+
+fn rotate(point: (f64, f64), theta: f64) -> (f64, f64){
+    (point.0 * theta.cos() - point.1 * theta.sin(), point.0 * theta.sin() + point.1 * theta.cos())
+}
+
+impl FourBarDimension{
+    // two triangles to solve the four-bar
+    // first : crank, diagonal (A to ground_b), ground (ground_b to ground_a), it is returned solved
+    // second : coupler, rocker (B to ground_b), diagonal (ground_b to A) taken from the first one
+    // only the angles of coupler and rocker are unknown in the second one, its two solutions are the branches
+    pub fn to_triangles(&self, crank_angle: f64) -> Result<[Triangle; 2], Error>{
+        let ground = VariableFRecVec2::from(self.ground_b.0 - self.ground_a.0, self.ground_b.1 - self.ground_a.1).to_pol();
+        let first = Triangle::new(VariableFPolVec2::from(self.crank, crank_angle), VariableFPolVec2{radius: VariableF::Unknown, theta: VariableF::Unknown}, -ground).solve()?;
+        let mut second = Triangle::from_len([self.coupler, self.rocker, 0.0]);
+        second.c = -first.b;
+        Ok([first, second])
+    }
+    pub fn solve(&self, crank_angle: f64, branch: FourBarBranch) -> Result<FourBarPosition, Error>{
+        let [first, second] = self.to_triangles(crank_angle)?;
+        let crank = fixed_rec(first.a)?;
+        let a = (self.ground_a.0 + crank.0, self.ground_a.1 + crank.1);
+        let mut coupler = None;
+        // B on the diagonal has only one solution, it belongs to both branches
+        for solution in second.solve_branches()?{
+            let candidate = fixed_rec(solution.a)?;
+            let side = branch_side(a, (a.0 + candidate.0, a.1 + candidate.1), self.ground_b);
+            if coupler.is_none() || (side > 0.0) == (branch == FourBarBranch::Left) {
+                coupler = Some(candidate);
+            }
+        }
+        let coupler = coupler.ok_or(Error::other("solve error : coupler is not solved"))?;
+        let b = (a.0 + coupler.0, a.1 + coupler.1);
+        let coupler_theta = coupler.1.atan2(coupler.0);
+        let offset = rotate(self.coupler_point, coupler_theta);
+        Ok(FourBarPosition{
+            a,
+            b,
+            coupler_point: (a.0 + offset.0, a.1 + offset.1),
+            coupler_theta,
+        })
+    }
+    // coupler curve of the branch sampled by num crank angles, positions that cannot be assembled are skipped
+    pub fn coupler_curve(&self, num: usize, branch: FourBarBranch) -> Vec<(f64, f64)>{
+        (0..num)
+            .filter_map(|i| self.solve(2.0 * std::f64::consts::PI * i as f64 / num as f64, branch).ok())
+            .map(|position| position.coupler_point)
+            .collect()
+    }
+    // assembly does not depend on the branch, both branches exist or neither does
+    pub fn can_assemble(&self, crank_angle: f64) -> bool{
+        self.solve(crank_angle, FourBarBranch::Left).is_ok()
+    }
+    // sweep the crank angle over range and find where assembly is lost
+    // return None if the four-bar can be assembled over the whole range
+    pub fn find_limit_position(&self, range: SweepRange) -> Result<Option<FourBarLimitPosition>, Error>{
+        let SweepRange{start, end, steps, tolerance} = range;
+        if steps == 0 || tolerance.is_nan() || tolerance <= 0.0 || !start.is_finite() || !end.is_finite() {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("steps {} and tolerance {} must be positive and range ({}, {}) must be finite", steps, tolerance, start, end)));
+        }
+        if !self.can_assemble(start){
+            return Err(Error::other("four-bar cannot be assembled at the start of the range"));
+        }
+        let (assembled, lost) = match limit_position::find_limit(start, end, steps, tolerance, |angle| self.can_assemble(angle)){
+            Some(bracket) => bracket,
+            None => return Ok(None),
+        };
+        // first triangle (crank, diagonal, ground) is always solved, so the second one is degenerate
+        let [_, second] = self.to_triangles(lost)?;
+        match (second.solve(), second.c.radius){
+            (Err(_), VariableF::Fixed(diagonal)) => Ok(Some(FourBarLimitPosition{
+                crank_angle: assembled,
+                triangle_index: 1,
+                stretched: diagonal > self.coupler + self.rocker,
+            })),
+            _ => Err(Error::other("limit position is not reproducible")),
+        }
+    }
+    // rocking range of the crank (min, max) around start_angle, crank is assembled in between
+    // return None if the crank can rotate fully
+    pub fn get_crank_range(&self, start_angle: f64, steps: usize, tolerance: f64) -> Result<Option<(f64, f64)>, Error>{
+        let turn = 2.0 * std::f64::consts::PI;
+        let Some(max) = self.find_limit_position(SweepRange{start: start_angle, end: start_angle + turn, steps, tolerance})? else {
+            return Ok(None);
+        };
+        let min = self.find_limit_position(SweepRange{start: start_angle, end: start_angle - turn, steps, tolerance})?
+            .ok_or(Error::other("limit position is not reproducible"))?;
+        Ok(Some((min.crank_angle, max.crank_angle)))
+    }
+    // four-bar as sim::Mechanism, drive it with MechInput{joint_index: 0, linkage_index: 1, angle: crank_angle}
+    // linkages : 0 ground, 1 crank, 2 coupler (A, B, coupler point), 3 rocker
+    // joints : 0 ground_a, 1 ground_b, 2 A, 3 B, 4 coupler point
+    pub fn to_mechanism(&self, branch: FourBarBranch) -> Mechanism{
+        let ground = Vector2::new(self.ground_b.0 - self.ground_a.0, self.ground_b.1 - self.ground_a.1);
+        let mut mechanism = Mechanism::from_linkages(vec![
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), ground]),
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(self.crank, 0.0)]),
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(self.coupler, 0.0), Vector2::new(self.coupler_point.0, self.coupler_point.1)]),
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(self.rocker, 0.0)]),
+        ]);
+        let joints = [
+            PinJoint::from_linkage([0, 1], [0, 0]).fix(Point2::new(self.ground_a.0, self.ground_a.1)),
+            PinJoint::from_linkage([0, 3], [1, 0]).fix(Point2::new(self.ground_b.0, self.ground_b.1)),
+            PinJoint::from_linkage([1, 2], [1, 0]),
+            PinJoint::from_linkage([2, 3], [1, 1]),
+            PinJoint::from_linkage([2], [2]),
+        ];
+        for joint in joints{
+            mechanism.add_joint(joint).expect("every connection of the four-bar exists");
+        }
+        // B is solved by the dyad of A and ground_b, branch is the side of B from the diagonal A to ground_b
+        mechanism.add_hint(SolveHint{joint_index_from: 2, joint_index_to: [1, 3], invert: branch == FourBarBranch::Right});
+        mechanism
+    }
+    // build dimension from pivots at one position of the coupler
+    fn from_pivots(ground_a: (f64, f64), ground_b: (f64, f64), a: (f64, f64), b: (f64, f64), coupler_point: (f64, f64)) -> Result<Self, Error>{
+        let length = |p: (f64, f64), q: (f64, f64)| ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2)).sqrt();
+        let coupler = length(a, b);
+        if coupler == 0.0 {return Err(Error::other("synthesis error : moving pivots are same point"));}
+        let coupler_theta = (b.1 - a.1).atan2(b.0 - a.0);
+        Ok(FourBarDimension{
+            ground_a,
+            ground_b,
+            crank: length(ground_a, a),
+            coupler,
+            rocker: length(ground_b, b),
+            coupler_point: rotate((coupler_point.0 - a.0, coupler_point.1 - a.1), -coupler_theta),
+        })
+    }
+}
+
+// position of a point fixed on the coupler, local is in the coupler frame of the position
+fn to_global(position: &CouplerPosition, local: (f64, f64)) -> (f64, f64){
+    let offset = rotate(local, position.theta);
+    (position.x + offset.0, position.y + offset.1)
+}
+
+fn circumcenter(p1: (f64, f64), p2: (f64, f64), p3: (f64, f64)) -> Result<(f64, f64), Error>{
+    let d = 2.0 * (p1.0 * (p2.1 - p3.1) + p2.0 * (p3.1 - p1.1) + p3.0 * (p1.1 - p2.1));
+    if d.abs() < 1e-12 {return Err(Error::other("synthesis error : three positions of the moving pivot are on a line"));}
+    let s1 = p1.0 * p1.0 + p1.1 * p1.1;
+    let s2 = p2.0 * p2.0 + p2.1 * p2.1;
+    let s3 = p3.0 * p3.0 + p3.1 * p3.1;
+    Ok((
+        (s1 * (p2.1 - p3.1) + s2 * (p3.1 - p1.1) + s3 * (p1.1 - p2.1)) / d,
+        (s1 * (p3.0 - p2.0) + s2 * (p1.0 - p3.0) + s3 * (p2.0 - p1.0)) / d,
+    ))
+}
+
+// two precision position motion generation
+// moving_pivots : A and B in the coupler frame (relative to the coupler point, rotated by theta)
+// bisector_offsets : where each ground pivot is placed on the perpendicular bisector, distance from the midpoint
+pub fn two_position_motion(positions: [CouplerPosition; 2], moving_pivots: [(f64, f64); 2], bisector_offsets: [f64; 2]) -> Result<MotionSynthesisResult, Error>{
+    let mut ground = [(0.0, 0.0); 2];
+    for i in 0..2{
+        let p1 = to_global(&positions[0], moving_pivots[i]);
+        let p2 = to_global(&positions[1], moving_pivots[i]);
+        let (dx, dy) = (p2.0 - p1.0, p2.1 - p1.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len < 1e-12 {return Err(Error::other("synthesis error : moving pivot does not move between two positions"));}
+        ground[i] = ((p1.0 + p2.0) / 2.0 - dy / len * bisector_offsets[i], (p1.1 + p2.1) / 2.0 + dx / len * bisector_offsets[i]);
+    }
+    motion_result(ground, &positions, moving_pivots)
+}
+
+// three precision position motion generation
+// moving_pivots : A and B in the coupler frame (relative to the coupler point, rotated by theta)
+// ground pivots are centers of circles through three positions of each moving pivot
+pub fn three_position_motion(positions: [CouplerPosition; 3], moving_pivots: [(f64, f64); 2]) -> Result<MotionSynthesisResult, Error>{
+    let mut ground = [(0.0, 0.0); 2];
+    for i in 0..2{
+        ground[i] = circumcenter(
+            to_global(&positions[0], moving_pivots[i]),
+            to_global(&positions[1], moving_pivots[i]),
+            to_global(&positions[2], moving_pivots[i]),
+        )?;
+    }
+    motion_result(ground, &positions, moving_pivots)
+}
+
+// four-bar through the precision positions with its crank angle at each of them
+// every position must be on the same branch, otherwise the linkage has to be disassembled between them (branch defect)
+fn motion_result(ground: [(f64, f64); 2], positions: &[CouplerPosition], moving_pivots: [(f64, f64); 2]) -> Result<MotionSynthesisResult, Error>{
+    let dimension = FourBarDimension::from_pivots(
+        ground[0],
+        ground[1],
+        to_global(&positions[0], moving_pivots[0]),
+        to_global(&positions[0], moving_pivots[1]),
+        (positions[0].x, positions[0].y),
+    )?;
+    let mut crank_angles = Vec::with_capacity(positions.len());
+    let mut branch = None;
+    for (index, position) in positions.iter().enumerate(){
+        let a = to_global(position, moving_pivots[0]);
+        let b = to_global(position, moving_pivots[1]);
+        crank_angles.push((a.1 - ground[0].1).atan2(a.0 - ground[0].0));
+        // B on the diagonal belongs to both branches
+        let side = branch_side(a, b, ground[1]);
+        if side.abs() < 1e-12 {continue;}
+        let position_branch = if side > 0.0 {FourBarBranch::Left} else {FourBarBranch::Right};
+        match branch{
+            None => branch = Some(position_branch),
+            Some(branch) if branch != position_branch => {
+                return Err(Error::other(format!("synthesis error : branch defect, position {} is on the other branch", index)));
+            },
+            Some(_) => {},
+        }
+    }
+    Ok(MotionSynthesisResult{
+        dimension,
+        branch: branch.unwrap_or(FourBarBranch::Left),
+        crank_angles,
+    })
+}
+
+// path generation by optimization
+// find a four-bar whose coupler curve passes near every target point, starting from initial
+// branch : assembly mode whose coupler curve is used
+// samples : number of crank angles to sample the coupler curve, must be positive
+pub fn path_generation(targets: &[(f64, f64)], initial: FourBarDimension, branch: FourBarBranch, samples: usize, max_iter: usize) -> Result<PathSynthesisResult, Error>{
+    if targets.is_empty() {return Err(Error::new(ErrorKind::InvalidInput, "synthesis error : no target point"));}
+    if samples == 0 {return Err(Error::new(ErrorKind::InvalidInput, "synthesis error : samples must be positive"));}
+    let to_dimension = |x: &[f64]| FourBarDimension{
+        ground_a: (x[0], x[1]),
+        ground_b: (x[2], x[3]),
+        crank: x[4],
+        coupler: x[5],
+        rocker: x[6],
+        coupler_point: (x[7], x[8]),
+    };
+    // mean square distance, curve that cannot be assembled at all is penalized
+    let error = |x: &[f64]| -> f64 {
+        if x[4] <= 0.0 || x[5] <= 0.0 || x[6] <= 0.0 {return f64::MAX;}
+        let curve = to_dimension(x).coupler_curve(samples, branch);
+        if curve.is_empty() {return f64::MAX;}
+        targets.iter().map(|target| {
+            curve.iter()
+                .map(|point| (point.0 - target.0).powi(2) + (point.1 - target.1).powi(2))
+                .fold(f64::MAX, f64::min)
+        }).sum::<f64>() / targets.len() as f64
+    };
+    let x0 = [
+        initial.ground_a.0, initial.ground_a.1, initial.ground_b.0, initial.ground_b.1,
+        initial.crank, initial.coupler, initial.rocker,
+        initial.coupler_point.0, initial.coupler_point.1,
+    ];
+    if error(&x0) == f64::MAX {return Err(Error::other("synthesis error : initial four-bar cannot be assembled"));}
+    let scale = initial.crank.max(initial.coupler).max(initial.rocker);
+    let (x, value) = optimize::nelder_mead(error, &x0, &[0.1 * scale; 9], max_iter, 1e-14);
+    Ok(PathSynthesisResult{
+        dimension: to_dimension(&x),
+        residual: value.sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    fn angle_diff(a: f64, b: f64) -> f64 {
+        (a - b + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI
+    }
+
+    const POSITIONS: [CouplerPosition; 3] = [
+        CouplerPosition{x: 0.0, y: 2.0, theta: 0.0},
+        CouplerPosition{x: 0.6, y: 2.3, theta: -0.2},
+        CouplerPosition{x: 1.2, y: 2.2, theta: -0.45},
+    ];
+    const MOVING_PIVOTS: [(f64, f64); 2] = [(-0.5, -0.3), (0.8, -0.2)];
+
+    // coupler point and angle of the synthesized four-bar must reproduce each precision position
+    fn assert_reproduces(result: &MotionSynthesisResult, positions: &[CouplerPosition]) {
+        assert_eq!(result.crank_angles.len(), positions.len());
+        let mut theta_offset = None;
+        for (position, &crank_angle) in positions.iter().zip(result.crank_angles.iter()) {
+            let solved = result.dimension.solve(crank_angle, result.branch).unwrap();
+            assert_close(solved.coupler_point, (position.x, position.y));
+            assert_close(solved.a, to_global(position, MOVING_PIVOTS[0]));
+            assert_close(solved.b, to_global(position, MOVING_PIVOTS[1]));
+            // angle of AB differs from the coupler angle by a constant
+            let offset = angle_diff(solved.coupler_theta, position.theta);
+            let first = *theta_offset.get_or_insert(offset);
+            assert!(angle_diff(offset, first).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn circumcenter_of_points_on_circle() {
+        let point = |t: f64| (1.0 + 3.0 * t.cos(), 2.0 + 3.0 * t.sin());
+        assert_close(circumcenter(point(0.1), point(1.7), point(4.0)).unwrap(), (1.0, 2.0));
+        assert!(circumcenter((0.0, 0.0), (1.0, 1.0), (2.0, 2.0)).is_err());
+    }
+
+    #[test]
+    fn three_position_motion_reproduces_every_pose() {
+        let result = three_position_motion(POSITIONS, MOVING_PIVOTS).unwrap();
+        assert_reproduces(&result, &POSITIONS);
+    }
+
+    #[test]
+    fn two_position_motion_reproduces_both_poses() {
+        let positions = [POSITIONS[0], POSITIONS[2]];
+        let result = two_position_motion(positions, MOVING_PIVOTS, [0.5, -0.7]).unwrap();
+        assert_reproduces(&result, &positions);
+    }
+
+    #[test]
+    fn second_triangle_has_the_diagonal_of_the_first() {
+        let result = three_position_motion(POSITIONS, MOVING_PIVOTS).unwrap();
+        let [first, second] = result.dimension.to_triangles(result.crank_angles[1]).unwrap();
+        let (diagonal, reversed) = ((-first.b).to_rec(), second.c.to_rec());
+        assert_eq!((diagonal.x, diagonal.y), (reversed.x, reversed.y));
+        assert_eq!(second.solve_branches().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn branches_share_crank_and_mirror_b() {
+        let result = three_position_motion(POSITIONS, MOVING_PIVOTS).unwrap();
+        let dimension = result.dimension;
+        let left = dimension.solve(result.crank_angles[1], FourBarBranch::Left).unwrap();
+        let right = dimension.solve(result.crank_angles[1], FourBarBranch::Right).unwrap();
+        assert_close(left.a, right.a);
+        assert!(branch_side(left.a, left.b, dimension.ground_b) > 0.0);
+        assert!(branch_side(right.a, right.b, dimension.ground_b) < 0.0);
+        let length = |p: (f64, f64), q: (f64, f64)| ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2)).sqrt();
+        for position in [left, right] {
+            assert!((length(position.a, position.b) - dimension.coupler).abs() < 1e-9);
+            assert!((length(dimension.ground_b, position.b) - dimension.rocker).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn crank_range_of_double_rocker() {
+        // crank cannot rotate fully, coupler and rocker are stretched at cos(angle) = (1.5^2 + 2^2 - 2.2^2) / (2 * 1.5 * 2)
+        let double_rocker = FourBarDimension{
+            ground_a: (0.0, 0.0),
+            ground_b: (2.0, 0.0),
+            crank: 1.5,
+            coupler: 1.0,
+            rocker: 1.2,
+            coupler_point: (0.5, 0.0),
+        };
+        let limit_angle = ((1.5f64.powi(2) + 4.0 - 2.2f64.powi(2)) / 6.0).acos();
+        let range = SweepRange{start: 0.0, end: std::f64::consts::PI, steps: 100, tolerance: 1e-12};
+        let limit = double_rocker.find_limit_position(range).unwrap().unwrap();
+        assert!((limit.crank_angle - limit_angle).abs() < 1e-9, "{} != {}", limit.crank_angle, limit_angle);
+        assert_eq!(limit.triangle_index, 1);
+        assert!(limit.stretched);
+        let (min, max) = double_rocker.get_crank_range(0.0, 100, 1e-12).unwrap().unwrap();
+        assert!((min + limit_angle).abs() < 1e-9 && (max - limit_angle).abs() < 1e-9, "({}, {})", min, max);
+        assert!(double_rocker.can_assemble(max) && double_rocker.can_assemble(min));
+        assert!(double_rocker.find_limit_position(SweepRange{steps: 0, ..range}).is_err());
+        assert!(double_rocker.find_limit_position(SweepRange{tolerance: 0.0, ..range}).is_err());
+        assert!(double_rocker.find_limit_position(SweepRange{start: std::f64::consts::PI, ..range}).is_err());
+
+        // crank of Grashof crank-rocker rotates fully
+        let crank_rocker = FourBarDimension{crank: 0.8, coupler: 2.2, rocker: 1.5, ..double_rocker};
+        assert!(crank_rocker.get_crank_range(0.0, 100, 1e-12).unwrap().is_none());
+    }
+
+    #[test]
+    fn folded_limit_is_not_stretched() {
+        // diagonal becomes shorter than rocker - coupler = 1.0 near crank angle 0
+        let folded = FourBarDimension{
+            ground_a: (0.0, 0.0),
+            ground_b: (2.0, 0.0),
+            crank: 1.5,
+            coupler: 0.5,
+            rocker: 1.5,
+            coupler_point: (0.5, 0.0),
+        };
+        let limit_angle = ((1.5f64.powi(2) + 4.0 - 1.0) / 6.0).acos();
+        let limit = folded.find_limit_position(SweepRange{start: 0.8, end: 0.0, steps: 100, tolerance: 1e-12}).unwrap().unwrap();
+        assert!((limit.crank_angle - limit_angle).abs() < 1e-9, "{} != {}", limit.crank_angle, limit_angle);
+        assert!(!limit.stretched);
+    }
+
+    #[test]
+    fn path_generation_recovers_perturbed_four_bar() {
+        let known = FourBarDimension{
+            ground_a: (0.0, 0.0),
+            ground_b: (2.0, 0.0),
+            crank: 0.8,
+            coupler: 2.2,
+            rocker: 1.5,
+            coupler_point: (1.0, 0.5),
+        };
+        // targets are on crank angles that are also sampled, so the known four-bar has zero residual
+        let targets = known.coupler_curve(12, FourBarBranch::Left);
+        assert_eq!(targets.len(), 12);
+        let initial = FourBarDimension{
+            ground_b: (2.05, -0.03),
+            crank: 0.84,
+            coupler: 2.15,
+            coupler_point: (1.06, 0.46),
+            ..known
+        };
+        // without iteration the result is the best point of the initial simplex
+        let initial_residual = path_generation(&targets, initial, FourBarBranch::Left, 360, 0).unwrap().residual;
+        let result = path_generation(&targets, initial, FourBarBranch::Left, 360, 3000).unwrap();
+        assert!(result.residual < 1e-2 && result.residual < 0.1 * initial_residual, "{} (initial {})", result.residual, initial_residual);
+        // every target is near the coupler curve of the result
+        let curve = result.dimension.coupler_curve(360, FourBarBranch::Left);
+        for target in targets.iter() {
+            let distance = curve.iter().map(|point| ((point.0 - target.0).powi(2) + (point.1 - target.1).powi(2)).sqrt()).fold(f64::MAX, f64::min);
+            assert!(distance < 5e-2, "{:?} is {} away", target, distance);
+        }
+    }
+
+    #[test]
+    fn path_generation_rejects_empty_input() {
+        let initial = FourBarDimension{
+            ground_a: (0.0, 0.0),
+            ground_b: (2.0, 0.0),
+            crank: 0.8,
+            coupler: 2.2,
+            rocker: 1.5,
+            coupler_point: (1.0, 0.5),
+        };
+        assert_eq!(path_generation(&[], initial, FourBarBranch::Left, 360, 100).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(path_generation(&[(1.0, 1.0)], initial, FourBarBranch::Left, 0, 100).unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -1,7 +1,8 @@
 pub mod variable_vector;
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 
 use variable_vector::*;
+use crate::ffi::{FfiError, LinkageSimError};
 
 // triangle must be clockwise three vector  (a,b,c)
 // a ↗↘ b
@@ -41,8 +42,10 @@ impl Triangle{
         (self.a, self.b, self.c) = (self.c, self.a, self.b);
         self
     }
+    // error of ErrorKind::InvalidInput means the unknowns of the triangle cannot be solved by any dimension
+    // other errors mean the triangle cannot be assembled with the dimension
     pub fn solve(mut self) -> Result<Self, Error>{
-        if self.get_dof() > 2 {return Err(Error::new(std::io::ErrorKind::Other, "solve error : dof > 2"));}
+        if self.get_dof() > 2 {return Err(Error::new(ErrorKind::InvalidInput, "solve error : dof > 2"));}
         match(self.a.get_dof(), self.b.get_dof(), self.c.get_dof()){
             (1|2, 0, 0) => self = self.solve_pattern1(),
             (0, 1|2, 0) => self = self.swap_cw().swap_cw().solve_pattern1().swap_cw(),
@@ -58,14 +61,35 @@ impl Triangle{
                     (0,1,0,1,0,0) => self = self.solve_pattern4()?,
                     (0,0,0,1,0,1) => self = self.swap_cw().swap_cw().solve_pattern4()?.swap_cw(),
                     (0,1,0,0,0,1) => self = self.swap_cw().solve_pattern4()?.swap_cw().swap_cw(),
-                    _ => return Err(Error::new(std::io::ErrorKind::Other, "solve error : dof pattern not found")),
+                    _ => return Err(Error::new(ErrorKind::InvalidInput, "solve error : dof pattern not found")),
                 }
             },
-            _ => return Err(Error::new(std::io::ErrorKind::Other, "solve error : dof pattern not found")),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "solve error : dof pattern not found")),
         }
         // write later
         Ok(self)
     }
+    // every solution of the triangle, first one is same as solve
+    // when two angles are unknown, the other branch is the mirror of the two vectors about the known one
+    pub fn solve_branches(self) -> Result<Vec<Self>, Error>{
+        let solved = self.solve()?;
+        let unknown_theta = [self.a.theta, self.b.theta, self.c.theta].map(|theta| theta == VariableF::Unknown);
+        let mut solutions = vec![solved];
+        if let (2, Some(known)) = (unknown_theta.iter().filter(|&&unknown| unknown).count(), unknown_theta.iter().position(|&unknown| !unknown)){
+            let sides = [solved.a, solved.b, solved.c];
+            let mirror_theta = VariableF::from(2.0) * sides[known].theta;
+            let mirror = [0, 1, 2].map(|i| if i == known {sides[i]} else {
+                VariableFPolVec2{radius: sides[i].radius, theta: mirror_theta - sides[i].theta}
+            });
+            // mirror of the collinear triangle is the same triangle
+            if let VariableF::Fixed(cross) = cross_product(sides[known].to_rec(), sides[(known + 1) % 3].to_rec()){
+                if cross.abs() > 1e-12 {
+                    solutions.push(Triangle{a: mirror[0], b: mirror[1], c: mirror[2]});
+                }
+            }
+        }
+        Ok(solutions)
+    }
     // pattern1
     // one vector that has unknown length and angle exists
     // a has unknown length and angle
@@ -81,7 +105,7 @@ impl Triangle{
         let a_norm = self.a.set_radius(1.0).to_rec();
         let b_norm = self.b.set_radius(1.0).to_rec();
         let a_cross_b: VariableF<f64> = cross_product(a_norm, b_norm);
-        if a_cross_b == VariableF::Fixed(0f64) {return Err(Error::new(std::io::ErrorKind::Other, "solve error : not triangle, only line"));}
+        if a_cross_b == VariableF::Fixed(0f64) {return Err(Error::other("solve error : not triangle, only line"));}
         (self.a.radius, self.b.radius) = {(
             cross_product(b_norm, self.c.to_rec()) / a_cross_b,
             - cross_product(a_norm, self.c.to_rec()) / a_cross_b,
@@ -93,7 +117,7 @@ impl Triangle{
     // a has unknown angle and b has unknown length
     fn solve_pattern3(self) -> Result<Self,Error> {
         // write later
-        return Err(Error::new(std::io::ErrorKind::Other, "solve error : method \"solve_pattern3\" is not implemented"));
+        Err(Error::new(ErrorKind::InvalidInput, "solve error : method \"solve_pattern3\" is not implemented"))
     }
     //pattern4
     // two vectors that have unknown angle exist
@@ -101,7 +125,7 @@ impl Triangle{
     fn solve_pattern4(mut self) -> Result<Self,Error> {
         if self.a.radius > self.b.radius + self.c.radius ||
             self.b.radius > self.c.radius + self.a.radius ||
-            self.c.radius > self.a.radius + self.b.radius {return Err(Error::new(std::io::ErrorKind::Other, "solve error : In this condition, it CANNOT be triangle"));}
+            self.c.radius > self.a.radius + self.b.radius {return Err(Error::other("solve error : In this condition, it CANNOT be triangle"));}
         let sol1_a_theta = (self.c).theta + ((self.b.radius * self.b.radius - self.a.radius * self.a.radius - self.c.radius * self.c.radius) / (VariableF::from(2.0) * self.a.radius * self.c.radius)).acos();
         let sol1_b_theta = self.c.theta - ((self.a.radius * self.a.radius - self.b.radius * self.b.radius - self.c.radius * self.c.radius) / (VariableF::from(2.0) * self.b.radius * self.c.radius)).acos();
         let sol2_a_theta = (self.c).theta - ((self.b.radius * self.b.radius - self.a.radius * self.a.radius - self.c.radius * self.c.radius) / (VariableF::from(2.0) * self.a.radius * self.c.radius)).acos();
@@ -116,4 +140,179 @@ impl Triangle{
         }
         Ok(self)
     }
-}
\ No newline at end of file
+}
+
+// triangle over the C ABI
+// radius and theta are ignored if they are unknown
+// radius_unknown and theta_unknown are flags, 0 is known and other values are unknown
+// they are u8 rather than bool so that C# and ctypes can marshal them without MarshalAs
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct triangle_side{
+    pub radius: f64,
+    pub theta: f64,
+    pub radius_unknown: u8,
+    pub theta_unknown: u8,
+}
+
+// a, b and c are clockwise three vectors as Triangle
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct triangle_description{
+    pub a: triangle_side,
+    pub b: triangle_side,
+    pub c: triangle_side,
+}
+
+// solutions[0] is the solution of Triangle::solve, solutions[1] is the other branch if num_solutions is 2
+// error is a LinkageSimError code, num_solutions is 0 on error
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct solve_triangle_return{
+    pub error: isize,
+    pub num_solutions: usize,
+    pub solutions: [triangle_description; 2],
+}
+
+impl triangle_side{
+    fn to_vec(self) -> Result<VariableFPolVec2, FfiError>{
+        let to_variable = |value: f64, unknown: u8| match (unknown != 0, value.is_finite()){
+            (true, _) => Ok(VariableF::Unknown),
+            (false, true) => Ok(VariableF::Fixed(value)),
+            (false, false) => Err(FfiError::new(LinkageSimError::InvalidInput, format!("{} is not finite", value))),
+        };
+        Ok(VariableFPolVec2{radius: to_variable(self.radius, self.radius_unknown)?, theta: to_variable(self.theta, self.theta_unknown)?})
+    }
+    fn from_vec(vec: VariableFPolVec2) -> Self{
+        let from_variable = |value: VariableF<f64>| match value{
+            VariableF::Fixed(value) => (value, 0),
+            VariableF::Unknown => (f64::NAN, 1),
+        };
+        let (radius, radius_unknown) = from_variable(vec.radius);
+        let (theta, theta_unknown) = from_variable(vec.theta);
+        triangle_side{radius, theta, radius_unknown, theta_unknown}
+    }
+}
+
+impl triangle_description{
+    fn to_triangle(self) -> Result<Triangle, FfiError>{
+        Ok(Triangle::new(self.a.to_vec()?, self.b.to_vec()?, self.c.to_vec()?))
+    }
+    fn from_triangle(triangle: Triangle) -> Self{
+        triangle_description{a: triangle_side::from_vec(triangle.a), b: triangle_side::from_vec(triangle.b), c: triangle_side::from_vec(triangle.c)}
+    }
+}
+
+impl solve_triangle_return{
+    fn failed(error: isize) -> Self{
+        let unknown = triangle_side{radius: f64::NAN, theta: f64::NAN, radius_unknown: 1, theta_unknown: 1};
+        let unknown = triangle_description{a: unknown, b: unknown, c: unknown};
+        solve_triangle_return{error, num_solutions: 0, solutions: [unknown; 2]}
+    }
+    // only the first two solutions are returned
+    fn ok(solutions: &[Triangle]) -> Self{
+        let mut ret = Self::failed(LinkageSimError::Ok as isize);
+        for (slot, solution) in ret.solutions.iter_mut().zip(solutions.iter()){
+            *slot = triangle_description::from_triangle(*solution);
+        }
+        ret.num_solutions = solutions.len().min(2);
+        ret
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn solve_triangle(triangle: triangle_description) -> solve_triangle_return{
+    crate::ffi::catch_panic(solve_triangle_return::failed(LinkageSimError::Panic as isize), || {
+        let solutions = triangle.to_triangle().and_then(|triangle| {
+            triangle.solve_branches().map_err(|error| {
+                let code = match error.kind(){
+                    ErrorKind::InvalidInput => LinkageSimError::InvalidInput,
+                    _ => LinkageSimError::CannotAssemble,
+                };
+                FfiError::new(code, error.to_string())
+            })
+        });
+        match solutions{
+            Ok(solutions) => solve_triangle_return::ok(&solutions),
+            Err(error) => solve_triangle_return::failed(error.into_code()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn rec(side: VariableFPolVec2) -> (f64, f64) {
+        match side.to_rec(){
+            VariableFRecVec2{x: VariableF::Fixed(x), y: VariableF::Fixed(y)} => (x, y),
+            _ => panic!("side is not solved"),
+        }
+    }
+
+    fn side(radius: f64, theta: f64, radius_unknown: u8, theta_unknown: u8) -> triangle_side {
+        triangle_side{radius, theta, radius_unknown, theta_unknown}
+    }
+
+    #[test]
+    fn two_unknown_angles_have_mirror_branches() {
+        // isosceles triangle on the known side c pointing to -x
+        let triangle = Triangle::new(VariableFPolVec2::from_len(1.5), VariableFPolVec2::from_len(1.5), VariableFPolVec2::from(2.0, PI));
+        let branches = triangle.solve_branches().unwrap();
+        assert_eq!(branches.len(), 2);
+        assert_eq!(rec(branches[0].a), rec(triangle.solve().unwrap().a));
+        let height = 1.25f64.sqrt();
+        for branch in &branches {
+            let [a, b, c] = [branch.a, branch.b, branch.c].map(rec);
+            assert!((a.0 + b.0 + c.0).abs() < 1e-12 && (a.1 + b.1 + c.1).abs() < 1e-12);
+            assert!((a.0 - 1.0).abs() < 1e-12 && (a.1.abs() - height).abs() < 1e-12);
+        }
+        // mirror about c
+        let (a0, a1) = (rec(branches[0].a), rec(branches[1].a));
+        assert!((a0.0 - a1.0).abs() < 1e-12 && (a0.1 + a1.1).abs() < 1e-12);
+        assert_eq!(branches[0].c.theta, branches[1].c.theta);
+        // collinear triangle has a single branch
+        assert_eq!(Triangle::new(VariableFPolVec2::from_len(1.0), VariableFPolVec2::from_len(1.0), VariableFPolVec2::from(2.0, PI)).solve_branches().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn solve_triangle_over_ffi() {
+        let code = |error: LinkageSimError| error as isize;
+        // any non-zero flag is unknown and the value is ignored
+        let triangle = triangle_description{a: side(1.5, f64::NAN, 0, 2), b: side(1.5, 0.0, 0, 1), c: side(2.0, PI, 0, 0)};
+        let ret = solve_triangle(triangle);
+        assert_eq!(ret.error, code(LinkageSimError::Ok));
+        assert_eq!(ret.num_solutions, 2);
+        let branches = triangle.to_triangle().ok().unwrap().solve_branches().unwrap();
+        for (solution, branch) in ret.solutions.iter().zip(&branches) {
+            for (side, expected) in [solution.a, solution.b, solution.c].iter().zip([branch.a, branch.b, branch.c]) {
+                assert_eq!((side.radius_unknown, side.theta_unknown), (0, 0));
+                assert_eq!(side.to_vec().ok().unwrap().radius, expected.radius);
+                assert_eq!(side.to_vec().ok().unwrap().theta, expected.theta);
+            }
+            assert_eq!((solution.c.radius, solution.c.theta), (2.0, PI));
+        }
+        // unknown round trips as NaN with the flag set
+        let unknown = triangle_side::from_vec(VariableFPolVec2::from_len(1.5));
+        assert_eq!((unknown.radius, unknown.radius_unknown, unknown.theta_unknown), (1.5, 0, 1));
+        assert!(unknown.theta.is_nan());
+        assert!(unknown.to_vec().ok().unwrap().theta == VariableF::Unknown);
+
+        let failed = |ret: solve_triangle_return| ret.num_solutions == 0 && ret.solutions[0].a.theta_unknown == 1;
+        // 0.5 + 1.0 < 2.0
+        let ret = solve_triangle(triangle_description{a: side(1.0, 0.0, 0, 1), b: side(0.5, 0.0, 0, 1), ..triangle});
+        assert_eq!(ret.error, code(LinkageSimError::CannotAssemble));
+        assert!(failed(ret));
+        let ret = solve_triangle(triangle_description{c: side(f64::INFINITY, PI, 0, 0), ..triangle});
+        assert_eq!(ret.error, code(LinkageSimError::InvalidInput));
+        assert!(failed(ret));
+        let ret = solve_triangle(triangle_description{a: side(1.5, f64::NAN, 0, 0), ..triangle});
+        assert_eq!(ret.error, code(LinkageSimError::InvalidInput));
+        // three unknown angles cannot be determined
+        let ret = solve_triangle(triangle_description{c: side(2.0, PI, 0, 1), ..triangle});
+        assert_eq!(ret.error, code(LinkageSimError::InvalidInput));
+        assert!(failed(ret));
+    }
+}
@@ -145,7 +145,7 @@ pub struct VariableFPolVec2{
 }
 
 impl VariableFPolVec2 {
-    pub fn to_rec(&self) -> VariableFRecVec2 {
+    pub fn to_rec(self) -> VariableFRecVec2 {
         match (self.radius, self.theta) {
             (VariableF::Fixed(r), VariableF::Fixed(t)) => VariableFRecVec2{x: VariableF::Fixed(r * t.cos()), y: VariableF::Fixed(r * t.sin())},
             _ => VariableFRecVec2{x: VariableF::Unknown, y: VariableF::Unknown},
@@ -220,7 +220,7 @@ pub struct VariableFRecVec2{
 }
 
 impl VariableFRecVec2 {
-    pub fn to_pol(&self) -> VariableFPolVec2 {
+    pub fn to_pol(self) -> VariableFPolVec2 {
         match (self.x, self.y) {
             (VariableF::Fixed(x), VariableF::Fixed(y)) => VariableFPolVec2{radius: VariableF::Fixed((x * x + y * y).sqrt()), theta: VariableF::Fixed(y.atan2(x))},
             _ => VariableFPolVec2{radius: VariableF::Unknown, theta: VariableF::Unknown},
@@ -287,4 +287,84 @@ impl ops::Div<f64> for VariableFRecVec2
     fn div(self, other: f64) -> Self {
         Self { x: self.x / VariableF::Fixed(other), y: self.y / VariableF::Fixed(other) }
     }
-}
\ No newline at end of file
+}
+
+//rectangular coordinated 3D vector
+#[derive(Debug, Clone, Copy)]
+pub struct VariableFRecVec3{
+    pub x : VariableF<f64>,
+    pub y : VariableF<f64>,
+    pub z : VariableF<f64>,
+}
+
+impl VariableFRecVec3 {
+    pub fn from(x : f64, y : f64, z : f64) -> Self{
+        VariableFRecVec3{x: VariableF::Fixed(x), y: VariableF::Fixed(y), z: VariableF::Fixed(z)}
+    }
+    // 2D vector on xy plane
+    pub fn from_rec2(vec : VariableFRecVec2) -> Self{
+        VariableFRecVec3{x: vec.x, y: vec.y, z: VariableF::Fixed(0.0)}
+    }
+    pub fn get_len(&self) -> VariableF<f64> {
+        match (self.x, self.y, self.z) {
+            (VariableF::Fixed(x), VariableF::Fixed(y), VariableF::Fixed(z)) => VariableF::Fixed((x * x + y * y + z * z).sqrt()),
+            _ => VariableF::Unknown,
+        }
+    }
+}
+
+pub fn cross_product_3d(a : VariableFRecVec3, b : VariableFRecVec3) -> VariableFRecVec3{
+    VariableFRecVec3{
+        x: a.y * b.z - a.z * b.y,
+        y: a.z * b.x - a.x * b.z,
+        z: a.x * b.y - a.y * b.x,
+    }
+}
+
+pub fn dot_product_3d(a : VariableFRecVec3, b : VariableFRecVec3) -> VariableF<f64>{
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+impl Variable for VariableFRecVec3 {
+    fn get_dof(&self) -> u16 {
+        self.x.get_dof() + self.y.get_dof() + self.z.get_dof()
+    }
+}
+
+impl ops::Add for VariableFRecVec3 {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        VariableFRecVec3{x: self.x + other.x, y: self.y + other.y, z: self.z + other.z}
+    }
+}
+
+impl ops::Sub for VariableFRecVec3 {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        VariableFRecVec3{x: self.x - other.x, y: self.y - other.y, z: self.z - other.z}
+    }
+}
+
+impl ops::Neg for VariableFRecVec3
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+impl ops::Mul<f64> for VariableFRecVec3
+{
+    type Output = Self;
+    fn mul(self, other: f64) -> Self {
+        Self { x: self.x * VariableF::Fixed(other), y: self.y * VariableF::Fixed(other), z: self.z * VariableF::Fixed(other) }
+    }
+}
+
+impl ops::Div<f64> for VariableFRecVec3
+{
+    type Output = Self;
+    fn div(self, other: f64) -> Self {
+        Self { x: self.x / VariableF::Fixed(other), y: self.y / VariableF::Fixed(other), z: self.z / VariableF::Fixed(other) }
+    }
+}
@@ -0,0 +1,215 @@
+use numpy::{ndarray::{Array2, Array3}, IntoPyArray, PyArray2, PyArray3};
+use pyo3::{create_exception, exceptions::{PyException, PyValueError}, prelude::*};
+
+use crate::mech_solver::triangle_solver::{self, variable_vector::{VariableF, VariableFPolVec2}};
+use crate::scissor_solver::{self, ScissorDimension, JOINTS_PER_ELEMENT};
+use crate::sim::{self, Linkage, PinJoint, MechInput, SolveHint};
+
+// Python extension module built with the "python" feature (e.g. maturin build --features python)
+// Triangle, Scissor and Mechanism are exposed as Python classes
+
+create_exception!(linkage_sim, SolveError, PyException);
+
+fn solve_error(error: impl ToString) -> PyErr{
+    SolveError::new_err(error.to_string())
+}
+
+fn to_variable(value: Option<f64>) -> VariableF<f64>{
+    value.map_or(VariableF::Unknown, VariableF::Fixed)
+}
+
+fn from_variable(value: VariableF<f64>) -> Option<f64>{
+    match value{
+        VariableF::Fixed(value) => Some(value),
+        VariableF::Unknown => None,
+    }
+}
+
+// side of the triangle is (radius, theta), None is unknown
+type Side = (Option<f64>, Option<f64>);
+
+fn to_side(vec: VariableFPolVec2) -> Side{
+    (from_variable(vec.radius), from_variable(vec.theta))
+}
+
+// Triangle(a, b, c) : a, b and c are clockwise sides, solve() fixes unknowns so that a + b + c = 0
+#[pyclass(name = "Triangle")]
+#[derive(Clone)]
+struct PyTriangle{
+    triangle: triangle_solver::Triangle,
+}
+
+#[pymethods]
+impl PyTriangle{
+    #[new]
+    fn new(a: Side, b: Side, c: Side) -> Self{
+        let to_vec = |side: Side| VariableFPolVec2{radius: to_variable(side.0), theta: to_variable(side.1)};
+        PyTriangle{triangle: triangle_solver::Triangle::new(to_vec(a), to_vec(b), to_vec(c))}
+    }
+    // lengths are fixed and every angle is unknown
+    #[staticmethod]
+    fn from_len(a: f64, b: f64, c: f64) -> Self{
+        PyTriangle{triangle: triangle_solver::Triangle::from_len([a, b, c])}
+    }
+    // solved copy of the triangle, raise SolveError if it cannot be solved
+    fn solve(&self) -> PyResult<Self>{
+        let triangle = self.triangle.solve().map_err(solve_error)?;
+        Ok(PyTriangle{triangle})
+    }
+    #[getter]
+    fn a(&self) -> Side{
+        to_side(self.triangle.a)
+    }
+    #[getter]
+    fn b(&self) -> Side{
+        to_side(self.triangle.b)
+    }
+    #[getter]
+    fn c(&self) -> Side{
+        to_side(self.triangle.c)
+    }
+    fn __repr__(&self) -> String{
+        let repr = |side: Side| {
+            let value = |value: Option<f64>| value.map_or("None".to_string(), |value| format!("{:?}", value));
+            format!("({}, {})", value(side.0), value(side.1))
+        };
+        format!("Triangle(a={}, b={}, c={})", repr(self.a()), repr(self.b()), repr(self.c()))
+    }
+}
+
+// Scissor(dimensions) : dimensions is a list of (a, b, c, d)
+#[pyclass(name = "Scissor")]
+#[derive(Clone)]
+struct PyScissor{
+    scissor: scissor_solver::Scissor,
+}
+
+#[pymethods]
+impl PyScissor{
+    #[new]
+    fn new(dimensions: Vec<(f64, f64, f64, f64)>) -> PyResult<Self>{
+        let scissor = scissor_solver::Scissor::new(dimensions.into_iter().map(|(a, b, c, d)| ScissorDimension{a, b, c, d}).collect())
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Ok(PyScissor{scissor})
+    }
+    // on error, the scissor keeps the previous solution
+    #[pyo3(signature = (input_radius, input_theta = 0.0))]
+    fn solve(&mut self, input_radius: f64, input_theta: f64) -> PyResult<()>{
+        let mut solved = self.scissor.clone();
+        solved.solve(VariableFPolVec2::from(input_radius, input_theta)).map_err(solve_error)?;
+        self.scissor = solved;
+        Ok(())
+    }
+    fn endpoint(&self) -> PyResult<(f64, f64)>{
+        self.scissor.get_endpoint().map_err(solve_error)
+    }
+    // joints of the solved scissor as array of shape (number of joints, 2), in the order of Scissor::get_joints
+    fn joints<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>>{
+        let joints = self.scissor.get_joints().map_err(solve_error)?;
+        let positions = Array2::from_shape_fn((joints.len(), 2), |(i, k)| if k == 0 {joints[i].x} else {joints[i].y});
+        Ok(positions.into_pyarray(py))
+    }
+    // joint trajectories of shape (number of inputs, number of joints, 2) for every input radius, the scissor itself is not changed
+    // joints of inputs where the scissor cannot be assembled are NaN
+    #[pyo3(signature = (input_radii, input_theta = 0.0))]
+    fn sweep<'py>(&self, py: Python<'py>, input_radii: Vec<f64>, input_theta: f64) -> Bound<'py, PyArray3<f64>>{
+        let num_joints = self.scissor.get_element_count() * JOINTS_PER_ELEMENT;
+        let mut trajectories = Array3::from_elem((input_radii.len(), num_joints, 2), f64::NAN);
+        let mut scissor = self.scissor.clone();
+        for (i, &radius) in input_radii.iter().enumerate(){
+            let joints = scissor.solve(VariableFPolVec2::from(radius, input_theta)).and_then(|()| scissor.get_joints());
+            if let Ok(joints) = joints{
+                for (j, joint) in joints.iter().enumerate(){
+                    trajectories[[i, j, 0]] = joint.x;
+                    trajectories[[i, j, 1]] = joint.y;
+                }
+            }
+        }
+        trajectories.into_pyarray(py)
+    }
+}
+
+// Mechanism(linkages) : linkages is a list of linkages, each linkage is a list of its joint points (x, y) in its local frame
+// joints connect (linkage index, joint index in the linkage) pairs and are referred by the index add_joint returns
+#[pyclass(name = "Mechanism")]
+#[derive(Clone)]
+struct PyMechanism{
+    mechanism: sim::Mechanism,
+}
+
+// input is (joint index, linkage index, angle), the linkage rotates around the joint by angle
+fn to_inputs(inputs: &[(usize, usize, f64)]) -> Vec<MechInput>{
+    inputs.iter().map(|&(joint_index, linkage_index, angle)| MechInput{joint_index, linkage_index, angle}).collect()
+}
+
+#[pymethods]
+impl PyMechanism{
+    #[new]
+    fn new(linkages: Vec<Vec<(f64, f64)>>) -> Self{
+        let linkages = linkages.iter().map(|points| {
+            Linkage::from_points(&points.iter().map(|&(x, y)| cgmath::Vector2::new(x, y)).collect::<Vec<_>>())
+        }).collect();
+        PyMechanism{mechanism: sim::Mechanism::from_linkages(linkages)}
+    }
+    // add joint and return its index, fixed is the ground position of the joint
+    #[pyo3(signature = (connections, fixed = None))]
+    fn add_joint(&mut self, connections: Vec<(usize, usize)>, fixed: Option<(f64, f64)>) -> PyResult<usize>{
+        let mut joint = connections.into_iter().fold(PinJoint::new(), |joint, (linkage, index)| joint.add_connection(linkage, index));
+        if let Some((x, y)) = fixed{
+            joint = joint.fix(cgmath::Point2::new(x, y));
+        }
+        self.mechanism.add_joint(joint).map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+    // joint_to is solved to the side where (joint_to[0] - joint_from) x (joint_to[1] - joint_from) is positive, negative if invert
+    #[pyo3(signature = (joint_from, joint_to, invert = false))]
+    fn add_hint(&mut self, joint_from: usize, joint_to: (usize, usize), invert: bool){
+        self.mechanism.add_hint(SolveHint{joint_index_from: joint_from, joint_index_to: [joint_to.0, joint_to.1], invert});
+    }
+    // on error, the mechanism keeps the previous solution
+    fn solve(&mut self, inputs: Vec<(usize, usize, f64)>) -> PyResult<()>{
+        self.mechanism.solve(&to_inputs(&inputs)).map_err(solve_error)
+    }
+    // joints of the solved mechanism as array of shape (number of joints, 2)
+    fn joints<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>>{
+        let joints = self.mechanism.get_joints().map_err(solve_error)?;
+        let positions = Array2::from_shape_fn((joints.len(), 2), |(i, k)| if k == 0 {joints[i].0} else {joints[i].1});
+        Ok(positions.into_pyarray(py))
+    }
+    // (joint index, transmission angle) of every dyad of the solved mechanism
+    fn transmission_angles(&self) -> PyResult<Vec<(usize, f64)>>{
+        self.mechanism.get_transmission_angles().map_err(solve_error)
+    }
+    fn mechanical_advantage(&self, input: usize, output_linkage: usize) -> PyResult<f64>{
+        self.mechanism.get_mechanical_advantage(input, output_linkage).map_err(solve_error)
+    }
+    // joint trajectories of shape (number of angles, number of joints, 2) where inputs[input] takes every angle, the mechanism itself is not changed
+    // joints of angles where the mechanism cannot be assembled are NaN
+    #[pyo3(signature = (inputs, input_angles, input = 0))]
+    fn sweep<'py>(&self, py: Python<'py>, inputs: Vec<(usize, usize, f64)>, input_angles: Vec<f64>, input: usize) -> PyResult<Bound<'py, PyArray3<f64>>>{
+        let mut inputs = to_inputs(&inputs);
+        if input >= inputs.len() {return Err(PyValueError::new_err(format!("input {} does not exist", input)));}
+        let mut trajectories = Array3::from_elem((input_angles.len(), self.mechanism.get_joint_count(), 2), f64::NAN);
+        let mut mechanism = self.mechanism.clone();
+        for (i, &angle) in input_angles.iter().enumerate(){
+            inputs[input].angle = angle;
+            let joints = mechanism.solve(&inputs).and_then(|()| mechanism.get_joints());
+            if let Ok(joints) = joints{
+                for (j, joint) in joints.iter().enumerate(){
+                    trajectories[[i, j, 0]] = joint.0;
+                    trajectories[[i, j, 1]] = joint.1;
+                }
+            }
+        }
+        Ok(trajectories.into_pyarray(py))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "linkage_sim")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()>{
+    m.add_class::<PyTriangle>()?;
+    m.add_class::<PyScissor>()?;
+    m.add_class::<PyMechanism>()?;
+    m.add("SolveError", m.py().get_type::<SolveError>())?;
+    Ok(())
+}
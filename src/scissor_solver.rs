@@ -1,7 +1,19 @@
-use core::slice;
+use std::f64::consts::PI;
 
-use plotters::{prelude::*, backend};
+use plotters::prelude::*;
+use thiserror::Error;
 use crate::mech_solver;
+use crate::ffi::{FfiError, LinkageSimError};
+use mech_solver::{limit_position, optimize};
+
+pub mod polar;
+pub mod curve_synthesis;
+pub mod actuator;
+pub mod boundary;
+pub mod network;
+pub mod collision;
+pub mod load;
+pub mod spatial;
 
 use mech_solver::triangle_solver::{Triangle,variable_vector::{self, VariableF}};
 // ScissorDimension has 4 length
@@ -18,12 +30,78 @@ pub struct ScissorDimension{
     pub d: f64,
 }
 
+// AngulatedScissorDimension is ScissorDimension whose bars are bent at the pivot
+// kink_a : angle of the outer part of a (pivot to endpoint) from the inner part (origin to pivot), counterclockwise (rad)
+// kink_b : same as kink_a for b
+// a and b are total length of the two parts, so a - c and b - d are length of the outer parts
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AngulatedScissorDimension{
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub kink_a: f64,
+    pub kink_b: f64,
+}
+
+impl From<ScissorDimension> for AngulatedScissorDimension{
+    fn from(dimension: ScissorDimension) -> Self{
+        AngulatedScissorDimension{a: dimension.a, b: dimension.b, c: dimension.c, d: dimension.d, kink_a: 0.0, kink_b: 0.0}
+    }
+}
+
+// rule of ScissorDimension that is broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScissorDimensionRule{
+    // a, b, c and d must be positive
+    NotPositive,
+    // a must be longer than c
+    ANotLongerThanC,
+    // b must be longer than d
+    BNotLongerThanD,
+    // kink_a and kink_b must be finite
+    KinkNotFinite,
+    // twist of SpatialScissorDimension must be finite
+    TwistNotFinite,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidScissorDimension{
+    pub element_index: usize,
+    pub rule: ScissorDimensionRule,
+}
+
+#[derive(Error, Debug)]
+pub enum ScissorNewErr{
+    #[error("Scissor must have at least 1 element.")]
+    NoElement,
+    #[error("Invalid ScissorDimension. {}", describe_invalid(.invalid))]
+    InvalidDimension{invalid: Vec<InvalidScissorDimension>},
+}
+
+fn describe_invalid(invalid: &[InvalidScissorDimension]) -> String{
+    invalid.iter().map(|invalid| format!("element {} : {}", invalid.element_index, match invalid.rule{
+        ScissorDimensionRule::NotPositive => "a, b, c and d must be positive",
+        ScissorDimensionRule::ANotLongerThanC => "a must be longer than c",
+        ScissorDimensionRule::BNotLongerThanD => "b must be longer than d",
+        ScissorDimensionRule::KinkNotFinite => "kink_a and kink_b must be finite",
+        ScissorDimensionRule::TwistNotFinite => "twist must be finite",
+    })).collect::<Vec<_>>().join(", ")
+}
+
+// a and b are vectors from origin to endpoint of the bars
+// a_outer and b_outer are vectors from the pivot to endpoint, bent by kink_a and kink_b
 #[derive(Clone)]
 struct ScissorElement{
     a: variable_vector::VariableFPolVec2,
     b: variable_vector::VariableFPolVec2,
     c: variable_vector::VariableFPolVec2,
     d: variable_vector::VariableFPolVec2,
+    a_outer: variable_vector::VariableFPolVec2,
+    b_outer: variable_vector::VariableFPolVec2,
+    kink_a: f64,
+    kink_b: f64,
 }
 
 impl ScissorElement{
@@ -31,36 +109,167 @@ impl ScissorElement{
     // output is a vector from the b endpoint to the a endpoint
     // you can use output as input of next element
     fn solve(&mut self, input: variable_vector::VariableFPolVec2) -> Result<variable_vector::VariableFPolVec2, std::io::Error>{
+        // direction of c and d is undefined (NaN) for input without length
+        if input.radius == VariableF::Fixed(0.0) {
+            return Err(std::io::Error::other("input of the element has no length"));
+        }
+        // forget previous solution so that solved element can be solved again
+        self.c.theta = VariableF::Unknown;
+        self.d.theta = VariableF::Unknown;
         let triangle = Triangle::new(self.c, -self.d, -input).solve()?;
         self.c = triangle.a;
         self.d = -triangle.b;
-        self.a.theta = self.c.theta;
-        self.b.theta = self.d.theta;
+        self.a_outer.theta = self.c.theta + VariableF::Fixed(self.kink_a);
+        self.b_outer.theta = self.d.theta + VariableF::Fixed(self.kink_b);
+        self.a = self.c + self.a_outer;
+        self.b = self.d + self.b_outer;
         Ok(self.a - self.b -input)
     }
+    // transmission angle is the angle between bar a and bar b at the pivot (0 ~ PI)
+    // for angulated element, inner parts (c and d) are used
+    fn get_transmission_angle(&self) -> Result<f64, std::io::Error>{
+        if let (VariableF::Fixed(a_theta), VariableF::Fixed(b_theta)) = (self.c.theta, self.d.theta){
+            let diff = (a_theta - b_theta).rem_euclid(2.0 * PI);
+            Ok(if diff > PI {2.0 * PI - diff} else {diff})
+        }else{
+            Err(std::io::Error::other("element is not solved"))
+        }
+    }
+}
+
+// ScissorSolveReport is the result of Scissor::solve_with_report
+// endpoint : position of the tip
+// transmission_angles : transmission angle of each element (rad)
+// mechanical_advantage : tip force / input force (ratio of input_radius displacement to tip displacement)
+// poor_transmission : index of elements whose transmission angle is out of [threshold, PI - threshold]
+#[derive(Debug, Clone)]
+pub struct ScissorSolveReport{
+    pub endpoint: (f64, f64),
+    pub transmission_angles: Vec<f64>,
+    pub mechanical_advantage: f64,
+    pub poor_transmission: Vec<usize>,
+}
+
+// ScissorLimitPosition is where the scissor cannot be assembled any more
+// input_radius : the last input_radius that can be assembled
+// element_index : index of the element whose triangle (c, d, input of the element) became degenerate
+// stretched : true if the element is fully opened (input of the element > c + d), false if fully closed
+#[derive(Debug, Clone, Copy)]
+pub struct ScissorLimitPosition{
+    pub input_radius: f64,
+    pub element_index: usize,
+    pub stretched: bool,
+}
+
+// role of the joint in the element
+// a_end of an element is b_origin of the next element, and b_end is a_origin of the next
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScissorJointRole{
+    AOrigin,
+    BOrigin,
+    Pivot,
+    AEnd,
+    BEnd,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ScissorJoint{
+    pub element_index: usize,
+    pub role: ScissorJointRole,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl ScissorJoint{
+    pub fn position(&self) -> (f64, f64){
+        (self.x, self.y)
+    }
 }
 
+// Scissor::get_joints returns joints of each element in this order
+const JOINT_ROLES: [ScissorJointRole; 5] = [ScissorJointRole::AOrigin, ScissorJointRole::BOrigin, ScissorJointRole::Pivot, ScissorJointRole::AEnd, ScissorJointRole::BEnd];
+pub const JOINTS_PER_ELEMENT: usize = JOINT_ROLES.len();
+
+// ownership of pointers across the C ABI
+// every pointer returned by this library is allocated by Rust and must be freed by the matching free function, never by libc free
+// create_scissor_dimension_array -> free_scissor_dimension_array (with the same size)
+// solve_from_scissor_dimension_array, solve_from_angulated_scissor_dimension_array -> free_solve_scissor_return
+// solve_joints_from_scissor_dimension_array -> free_solve_scissor_joints_return
+// scissor_new -> scissor_free
+// mechanism_new -> mechanism_free
+// pointers passed to the library are only borrowed during the call, caller keeps the ownership
+// error is a LinkageSimError code, linkage_sim_last_error_message describes the last error on the thread
+// freeing null pointer is allowed and does nothing
+
 #[no_mangle]
 pub extern "C" fn create_scissor_dimension_array(size: usize) -> *const ScissorDimension {
-    let vec: Vec<ScissorDimension> = vec![ScissorDimension{a:1.0, b:1.0, c:0.5, d:0.5}; size];
-    let slice = vec.into_boxed_slice();
-    Box::into_raw(slice) as *const ScissorDimension
+    crate::ffi::catch_panic(std::ptr::null(), || {
+        let vec: Vec<ScissorDimension> = vec![ScissorDimension{a:1.0, b:1.0, c:0.5, d:0.5}; size];
+        let slice = vec.into_boxed_slice();
+        Box::into_raw(slice) as *const ScissorDimension
+    })
 }
 
+/// array must be created by create_scissor_dimension_array with size len (or be any array of len ScissorDimension)
+/// element at index is written to out, out is not changed on error
+/// returns LinkageSimError, IndexOutOfRange if index >= len
+///
+/// # Safety
+///
+/// array must be null or point to len readable ScissorDimension, out must be null or point to a writable ScissorDimension.
 #[no_mangle]
-pub extern "C" fn get_scissor_dimension_array_element(array: *const ScissorDimension, index: usize) -> ScissorDimension {
-    let slice = unsafe { std::slice::from_raw_parts(array, index + 1) };
-    slice[index]
+pub unsafe extern "C" fn get_scissor_dimension_array_element(array: *const ScissorDimension, len: usize, index: usize, out: *mut ScissorDimension) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        match checked_scissor_dimension_index(array, len, index){
+            Ok(()) if out.is_null() => FfiError::new(LinkageSimError::NullPointer, "out is null").into_code(),
+            Ok(()) => {
+                unsafe { out.write(std::slice::from_raw_parts(array, len)[index]) };
+                LinkageSimError::Ok as isize
+            },
+            Err(error) => error.into_code(),
+        }
+    })
 }
 
+/// same as get_scissor_dimension_array_element, value is written at index
+///
+/// # Safety
+///
+/// array must be null or point to len writable ScissorDimension.
 #[no_mangle]
-pub extern "C" fn set_scissor_dimension_array_element(array: *mut ScissorDimension, index: usize, value: ScissorDimension) {
-    unsafe {
-        // 簡素化のためにindexが範囲内にあるか確認するなど、必要なチェックをチェックを省略しています。
-        
-        let array_ptr = array.add(index);
-        array_ptr.write(value);
+pub unsafe extern "C" fn set_scissor_dimension_array_element(array: *mut ScissorDimension, len: usize, index: usize, value: ScissorDimension) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        match checked_scissor_dimension_index(array, len, index){
+            Ok(()) => {
+                unsafe { std::slice::from_raw_parts_mut(array, len)[index] = value };
+                LinkageSimError::Ok as isize
+            },
+            Err(error) => error.into_code(),
+        }
+    })
+}
+
+fn checked_scissor_dimension_index(array: *const ScissorDimension, len: usize, index: usize) -> Result<(), FfiError> {
+    if array.is_null() {
+        return Err(FfiError::new(LinkageSimError::NullPointer, "array is null"));
     }
+    if index >= len {
+        return Err(FfiError::new(LinkageSimError::IndexOutOfRange, format!("index {} is out of range for array of length {}", index, len)));
+    }
+    Ok(())
+}
+
+/// array must be created by create_scissor_dimension_array with size len
+///
+/// # Safety
+///
+/// array must be null or returned by create_scissor_dimension_array with size len, and must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn free_scissor_dimension_array(array: *mut ScissorDimension, len: usize) {
+    if array.is_null() {return;}
+    crate::ffi::catch_panic((), || unsafe { drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(array, len))) });
 }
 
 #[repr(C)]
@@ -80,49 +289,69 @@ pub struct solve_scissor_return{
     pub lines: *const line,
 }
 
-#[no_mangle]
-pub extern "C" fn solve_from_scissor_dimension_array(array: *const ScissorDimension, len: usize, input_radius: f64, input_theta: f64) -> solve_scissor_return {
-    let slice = unsafe { std::slice::from_raw_parts(array, len) };
-    let mut scissor = Scissor::new(slice.to_vec());
-    if let Err(_) = scissor.solve(variable_vector::VariableFPolVec2::from(input_radius, input_theta)) {
-        return solve_scissor_return{
-            error: 1,
+impl solve_scissor_return{
+    fn failed(error: isize) -> Self{
+        solve_scissor_return{
+            error,
             num_lines: 0,
             lines: std::ptr::null(),
-        };
+        }
     }
-    let mut lines = Vec::<line>::new();
-    let mut next_vec_origin = (0.0, 0.0);
-    let mut next_vec_input = 
-    if let variable_vector::VariableFRecVec2{x: VariableF::Fixed(x),y: VariableF::Fixed(y)} = scissor.input.to_rec(){
-        (x, y)
-    }else{
-        panic!("input must be fixed");
+}
+
+/// # Safety
+///
+/// array must be null or point to len readable ScissorDimension.
+#[no_mangle]
+pub unsafe extern "C" fn solve_from_scissor_dimension_array(array: *const ScissorDimension, len: usize, input_radius: f64, input_theta: f64) -> solve_scissor_return {
+    crate::ffi::catch_panic(solve_scissor_return::failed(LinkageSimError::Panic as isize), || {
+        if array.is_null() {return solve_scissor_return::failed(FfiError::new(LinkageSimError::NullPointer, "array is null").into_code());}
+        let slice = unsafe { std::slice::from_raw_parts(array, len) };
+        solve_scissor_to_lines(Scissor::new(slice.to_vec()), input_radius, input_theta, false)
+    })
+}
+
+/// same as solve_from_scissor_dimension_array but each bar is returned as 2 lines (origin to pivot, pivot to endpoint)
+/// lines are ordered a inner, a outer, b inner, b outer for each element
+///
+/// # Safety
+///
+/// array must be null or point to len readable AngulatedScissorDimension.
+#[no_mangle]
+pub unsafe extern "C" fn solve_from_angulated_scissor_dimension_array(array: *const AngulatedScissorDimension, len: usize, input_radius: f64, input_theta: f64) -> solve_scissor_return {
+    crate::ffi::catch_panic(solve_scissor_return::failed(LinkageSimError::Panic as isize), || {
+        if array.is_null() {return solve_scissor_return::failed(FfiError::new(LinkageSimError::NullPointer, "array is null").into_code());}
+        let slice = unsafe { std::slice::from_raw_parts(array, len) };
+        solve_scissor_to_lines(Scissor::new_angulated(slice.to_vec()), input_radius, input_theta, true)
+    })
+}
+
+/// ret must be returned by solve_from_scissor_dimension_array or solve_from_angulated_scissor_dimension_array
+///
+/// # Safety
+///
+/// ret must be returned by solve_from_scissor_dimension_array or solve_from_angulated_scissor_dimension_array without modification and must be freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn free_solve_scissor_return(ret: solve_scissor_return) {
+    if ret.lines.is_null() {return;}
+    crate::ffi::catch_panic((), || unsafe { drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ret.lines as *mut line, ret.num_lines))) });
+}
+
+fn solve_scissor_to_lines(scissor: Result<Scissor, ScissorNewErr>, input_radius: f64, input_theta: f64, split_at_pivot: bool) -> solve_scissor_return {
+    let joints = match solve_scissor_to_joints(scissor, input_radius, input_theta){
+        Ok(joints) => joints,
+        Err(error) => return solve_scissor_return::failed(error.into_code()),
     };
-    for i in 0..len{
-        let element = &scissor.elements[i];
-        if let (
-            variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(ax), y: VariableF::<f64>::Fixed(ay)},
-            variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(bx), y: VariableF::<f64>::Fixed(by)}
-        ) = (element.a.to_rec(), element.b.to_rec()) {
-            lines.push(line{
-                x1: next_vec_origin.0,
-                y1: next_vec_origin.1,
-                x2: next_vec_origin.0 + ax,
-                y2: next_vec_origin.1 + ay,
-            });
-            lines.push(line{
-                x1: next_vec_origin.0 + next_vec_input.0,
-                y1: next_vec_origin.1 + next_vec_input.1,
-                x2: next_vec_origin.0 + next_vec_input.0 + bx,
-                y2: next_vec_origin.1 + next_vec_input.1 + by,
-            });
-            next_vec_origin.0 = next_vec_origin.0 + next_vec_input.0 + bx;
-            next_vec_origin.1 = next_vec_origin.1 + next_vec_input.1 + by;
-            next_vec_input.0 = ax - next_vec_input.0 - bx;
-            next_vec_input.1 = ay - next_vec_input.1 - by;
-        }else{
-            panic!("not fixed at element {}", i);
+    let mut lines = Vec::<line>::new();
+    for element_joints in joints.chunks(JOINTS_PER_ELEMENT){
+        let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| element_joints[i].position());
+        let bars = if split_at_pivot {
+            vec![(a_origin, pivot), (pivot, a_end), (b_origin, pivot), (pivot, b_end)]
+        } else {
+            vec![(a_origin, a_end), (b_origin, b_end)]
+        };
+        for (from, to) in bars{
+            lines.push(line{x1: from.0, y1: from.1, x2: to.0, y2: to.1});
         }
     }
     let lines_slice = lines.into_boxed_slice();
@@ -133,6 +362,259 @@ pub extern "C" fn solve_from_scissor_dimension_array(array: *const ScissorDimens
     }
 }
 
+fn solve_scissor_to_joints(scissor: Result<Scissor, ScissorNewErr>, input_radius: f64, input_theta: f64) -> Result<Vec<ScissorJoint>, FfiError> {
+    let mut scissor = scissor?;
+    solve_scissor_for_ffi(&mut scissor, input_radius, input_theta)?;
+    scissor.get_joints().map_err(|error| FfiError::new(LinkageSimError::Unknown, error.to_string()))
+}
+
+fn solve_scissor_for_ffi(scissor: &mut Scissor, input_radius: f64, input_theta: f64) -> Result<(), FfiError> {
+    if !input_radius.is_finite() || !input_theta.is_finite() {
+        return Err(FfiError::new(LinkageSimError::InvalidInput, format!("input ({}, {}) is not finite", input_radius, input_theta)));
+    }
+    scissor.solve_elements(variable_vector::VariableFPolVec2::from(input_radius, input_theta))
+        .map_err(|(index, _, error)| match error.kind(){
+            std::io::ErrorKind::InvalidInput => FfiError::new(LinkageSimError::InvalidInput, error.to_string()),
+            _ => FfiError::new(LinkageSimError::CannotAssemble, format!("cannot be assembled at element {} : {}", index, error)),
+        })
+}
+
+impl From<ScissorNewErr> for FfiError{
+    fn from(error: ScissorNewErr) -> Self{
+        let code = match error{
+            ScissorNewErr::NoElement => LinkageSimError::NoElement,
+            ScissorNewErr::InvalidDimension{..} => LinkageSimError::InvalidDimension,
+        };
+        FfiError::new(code, error.to_string())
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct solve_scissor_joints_return{
+    pub error: isize,
+    pub num_joints: usize,
+    pub joints: *const ScissorJoint,
+}
+
+impl solve_scissor_joints_return{
+    fn failed(error: isize) -> Self{
+        solve_scissor_joints_return{
+            error,
+            num_joints: 0,
+            joints: std::ptr::null(),
+        }
+    }
+}
+
+/// joints are ordered as Scissor::get_joints
+///
+/// # Safety
+///
+/// array must be null or point to len readable ScissorDimension.
+#[no_mangle]
+pub unsafe extern "C" fn solve_joints_from_scissor_dimension_array(array: *const ScissorDimension, len: usize, input_radius: f64, input_theta: f64) -> solve_scissor_joints_return {
+    crate::ffi::catch_panic(solve_scissor_joints_return::failed(LinkageSimError::Panic as isize), || {
+        if array.is_null() {return solve_scissor_joints_return::failed(FfiError::new(LinkageSimError::NullPointer, "array is null").into_code());}
+        let slice = unsafe { std::slice::from_raw_parts(array, len) };
+        match solve_scissor_to_joints(Scissor::new(slice.to_vec()), input_radius, input_theta){
+            Ok(joints) => {
+                let joints_slice = joints.into_boxed_slice();
+                solve_scissor_joints_return{
+                    error: 0,
+                    num_joints: joints_slice.len(),
+                    joints: Box::into_raw(joints_slice) as *const ScissorJoint,
+                }
+            },
+            Err(error) => solve_scissor_joints_return::failed(error.into_code()),
+        }
+    })
+}
+
+/// ret must be returned by solve_joints_from_scissor_dimension_array
+///
+/// # Safety
+///
+/// ret must be returned by solve_joints_from_scissor_dimension_array without modification and must be freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn free_solve_scissor_joints_return(ret: solve_scissor_joints_return) {
+    if ret.joints.is_null() {return;}
+    crate::ffi::catch_panic((), || unsafe { drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ret.joints as *mut ScissorJoint, ret.num_joints))) });
+}
+
+// opaque handle API, the handle keeps the Scissor alive across many solves
+// handle is created by scissor_new and must be freed by scissor_free
+
+/// null is returned if the scissor cannot be created, see linkage_sim_last_error_message
+///
+/// # Safety
+///
+/// array must be null or point to len readable ScissorDimension.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_new(array: *const ScissorDimension, len: usize) -> *mut Scissor {
+    crate::ffi::catch_panic(std::ptr::null_mut(), || {
+        if array.is_null() {
+            FfiError::new(LinkageSimError::NullPointer, "array is null").into_code();
+            return std::ptr::null_mut();
+        }
+        let slice = unsafe { std::slice::from_raw_parts(array, len) };
+        match Scissor::new(slice.to_vec()){
+            Ok(scissor) => Box::into_raw(Box::new(scissor)),
+            Err(error) => {
+                FfiError::from(error).into_code();
+                std::ptr::null_mut()
+            },
+        }
+    })
+}
+
+/// on error, the scissor keeps the previous solution
+///
+/// # Safety
+///
+/// scissor must be null or a live handle returned by scissor_new, not used by another thread during the call.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_solve(scissor: *mut Scissor, input_radius: f64, input_theta: f64) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let scissor = match unsafe { scissor.as_mut() }{
+            Some(scissor) => scissor,
+            None => return FfiError::new(LinkageSimError::NullPointer, "scissor is null").into_code(),
+        };
+        let mut solved = scissor.clone();
+        match solve_scissor_for_ffi(&mut solved, input_radius, input_theta){
+            Ok(()) => {
+                *scissor = solved;
+                LinkageSimError::Ok as isize
+            },
+            Err(error) => error.into_code(),
+        }
+    })
+}
+
+/// # Safety
+///
+/// scissor must be null or a live handle returned by scissor_new, x and y must be null or point to a writable double.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_get_endpoint(scissor: *const Scissor, x: *mut f64, y: *mut f64) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let scissor = match unsafe { scissor.as_ref() }{
+            Some(scissor) if !x.is_null() && !y.is_null() => scissor,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "scissor, x or y is null").into_code(),
+        };
+        match scissor.get_endpoint(){
+            Ok(endpoint) => {
+                unsafe {
+                    x.write(endpoint.0);
+                    y.write(endpoint.1);
+                }
+                LinkageSimError::Ok as isize
+            },
+            Err(error) => FfiError::new(LinkageSimError::NotSolved, error.to_string()).into_code(),
+        }
+    })
+}
+
+/// joints are written to buffer in the order of Scissor::get_joints
+/// num_joints : number of joints of the scissor, written even if capacity is too small so that the caller can allocate the buffer
+/// returns BufferTooSmall and writes no joint if capacity < num_joints
+///
+/// # Safety
+///
+/// scissor must be null or a live handle returned by scissor_new, num_joints must be null or point to a writable size_t, buffer must be null or point to capacity writable ScissorJoint.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_get_joints(scissor: *const Scissor, buffer: *mut ScissorJoint, capacity: usize, num_joints: *mut usize) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let scissor = match unsafe { scissor.as_ref() }{
+            Some(scissor) if !num_joints.is_null() => scissor,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "scissor or num_joints is null").into_code(),
+        };
+        let joints = match scissor.get_joints(){
+            Ok(joints) => joints,
+            Err(error) => return FfiError::new(LinkageSimError::NotSolved, error.to_string()).into_code(),
+        };
+        unsafe { num_joints.write(joints.len()) };
+        if capacity < joints.len() {
+            return FfiError::new(LinkageSimError::BufferTooSmall, format!("{} joints do not fit in buffer of capacity {}", joints.len(), capacity)).into_code();
+        }
+        if buffer.is_null() {
+            return FfiError::new(LinkageSimError::NullPointer, "buffer is null").into_code();
+        }
+        unsafe { std::slice::from_raw_parts_mut(buffer, joints.len()).copy_from_slice(&joints) };
+        LinkageSimError::Ok as isize
+    })
+}
+
+// result of one input of scissor_solve_sweep
+// error : LinkageSimError of the sample, x and y are NaN if it is not 0
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct scissor_sweep_sample{
+    pub error: isize,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// solve the scissor for every input and write the endpoint of each sample, the handle itself is not changed
+/// input_radii, input_thetas and samples have num_samples elements
+/// joints : optional (can be null), joints of sample i are written from joints[i * num_joints] in the order of Scissor::get_joints
+/// num_joints is JOINTS_PER_ELEMENT * number of elements, joints_capacity must be at least num_samples * num_joints
+/// joints of failed samples are not written
+/// returns error only if the arguments are invalid, failure of each sample is in its error
+///
+/// # Safety
+///
+/// scissor must be null or a live handle returned by scissor_new, input_radii and input_thetas must be null or point to num_samples readable double,
+/// samples must be null or point to num_samples writable scissor_sweep_sample, joints must be null or point to joints_capacity writable ScissorJoint.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_solve_sweep(scissor: *const Scissor, input_radii: *const f64, input_thetas: *const f64, num_samples: usize, samples: *mut scissor_sweep_sample, joints: *mut ScissorJoint, joints_capacity: usize) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let scissor = match unsafe { scissor.as_ref() }{
+            Some(scissor) if !input_radii.is_null() && !input_thetas.is_null() && !samples.is_null() => scissor,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "scissor, input_radii, input_thetas or samples is null").into_code(),
+        };
+        let num_joints = scissor.elements.len() * JOINTS_PER_ELEMENT;
+        let total_joints = match num_samples.checked_mul(num_joints){
+            Some(total_joints) => total_joints,
+            None => return FfiError::new(LinkageSimError::InvalidInput, format!("num_samples {} is too large", num_samples)).into_code(),
+        };
+        let mut joints = if joints.is_null() {
+            None
+        } else if joints_capacity < total_joints {
+            return FfiError::new(LinkageSimError::BufferTooSmall, format!("{} joints do not fit in buffer of capacity {}", total_joints, joints_capacity)).into_code();
+        } else {
+            Some(unsafe { std::slice::from_raw_parts_mut(joints, total_joints) })
+        };
+        let input_radii = unsafe { std::slice::from_raw_parts(input_radii, num_samples) };
+        let input_thetas = unsafe { std::slice::from_raw_parts(input_thetas, num_samples) };
+        let samples = unsafe { std::slice::from_raw_parts_mut(samples, num_samples) };
+        let mut solved = scissor.clone();
+        for (i, sample) in samples.iter_mut().enumerate(){
+            let sample_joints = solve_scissor_for_ffi(&mut solved, input_radii[i], input_thetas[i])
+                .and_then(|()| solved.get_joints().map_err(|error| FfiError::new(LinkageSimError::Unknown, error.to_string())));
+            *sample = match sample_joints{
+                Ok(sample_joints) => {
+                    if let Some(joints) = joints.as_deref_mut(){
+                        joints[i * num_joints..(i + 1) * num_joints].copy_from_slice(&sample_joints);
+                    }
+                    let (x, y) = sample_joints[num_joints - 1].position();
+                    scissor_sweep_sample{error: LinkageSimError::Ok as isize, x, y}
+                },
+                Err(error) => scissor_sweep_sample{error: error.into_code(), x: f64::NAN, y: f64::NAN},
+            };
+        }
+        LinkageSimError::Ok as isize
+    })
+}
+
+/// # Safety
+///
+/// scissor must be null or a handle returned by scissor_new that is not freed yet, it must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn scissor_free(scissor: *mut Scissor) {
+    if scissor.is_null() {return;}
+    crate::ffi::catch_panic((), || unsafe { drop(Box::from_raw(scissor)) });
+}
+
 #[derive(Clone)]
 pub struct Scissor{
     elements: Vec<ScissorElement>,
@@ -140,14 +622,31 @@ pub struct Scissor{
 }
 
 impl Scissor{
-    pub fn new(dimensions: Vec<ScissorDimension>) -> Self{
-        for dimension in dimensions.iter(){
-            if dimension.a <= 0.0 || dimension.b <= 0.0 || dimension.c <= 0.0 || dimension.d <= 0.0{
-                panic!("ScissorDimension must be positive");
+    pub fn new(dimensions: Vec<ScissorDimension>) -> Result<Self, ScissorNewErr>{
+        Self::new_angulated(dimensions.into_iter().map(AngulatedScissorDimension::from).collect())
+    }
+    pub fn new_angulated(dimensions: Vec<AngulatedScissorDimension>) -> Result<Self, ScissorNewErr>{
+        if dimensions.is_empty(){
+            return Err(ScissorNewErr::NoElement);
+        }
+        let mut invalid = Vec::new();
+        for (element_index, dimension) in dimensions.iter().enumerate(){
+            // NaN is also rejected
+            if !(dimension.a > 0.0 && dimension.b > 0.0 && dimension.c > 0.0 && dimension.d > 0.0){
+                invalid.push(InvalidScissorDimension{element_index, rule: ScissorDimensionRule::NotPositive});
+            }
+            if dimension.a.partial_cmp(&dimension.c) != Some(std::cmp::Ordering::Greater){
+                invalid.push(InvalidScissorDimension{element_index, rule: ScissorDimensionRule::ANotLongerThanC});
             }
-            if dimension.a <= dimension.c || dimension.b <= dimension.d{
-                panic!("ScissorDimension must be a > c, b > d");
+            if dimension.b.partial_cmp(&dimension.d) != Some(std::cmp::Ordering::Greater){
+                invalid.push(InvalidScissorDimension{element_index, rule: ScissorDimensionRule::BNotLongerThanD});
             }
+            if !(dimension.kink_a.is_finite() && dimension.kink_b.is_finite()){
+                invalid.push(InvalidScissorDimension{element_index, rule: ScissorDimensionRule::KinkNotFinite});
+            }
+        }
+        if !invalid.is_empty(){
+            return Err(ScissorNewErr::InvalidDimension{invalid});
         }
         let mut elements = Vec::new();
         for dimension in dimensions.iter(){
@@ -155,84 +654,463 @@ impl Scissor{
             let b = variable_vector::VariableFPolVec2::from_len(dimension.b);
             let c = variable_vector::VariableFPolVec2::from_len(dimension.c);
             let d = variable_vector::VariableFPolVec2::from_len(dimension.d);
-            elements.push(ScissorElement{a,b,c,d});
+            let a_outer = variable_vector::VariableFPolVec2::from_len(dimension.a - dimension.c);
+            let b_outer = variable_vector::VariableFPolVec2::from_len(dimension.b - dimension.d);
+            elements.push(ScissorElement{a, b, c, d, a_outer, b_outer, kink_a: dimension.kink_a, kink_b: dimension.kink_b});
         }
         let input = variable_vector::VariableFPolVec2{radius: VariableF::Unknown, theta: VariableF::Unknown};
-        Scissor{
+        Ok(Scissor{
             elements,
             input,
-        }
+        })
     }
     pub fn solve(&mut self, input: variable_vector::VariableFPolVec2) -> Result<(), std::io::Error>{
         if let (VariableF::Unknown, VariableF::Unknown) = (input.radius, input.theta){
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "input must be fixed"));
+            return Err(std::io::Error::other("input must be fixed"));
+        }
+        self.solve_elements(input).map_err(|(index, _, error)| std::io::Error::new(error.kind(), format!("at element {} : {}", index, error)))
+    }
+    // on error, return index of the element that cannot be solved and its input
+    // input_radius must be positive, error of ErrorKind::InvalidInput is returned at element 0 otherwise
+    fn solve_elements(&mut self, input: variable_vector::VariableFPolVec2) -> Result<(), (usize, variable_vector::VariableFPolVec2, std::io::Error)>{
+        if let VariableF::Fixed(radius) = input.radius {
+            if radius.is_nan() || radius <= 0.0 {
+                let error = std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("input_radius {} is not positive", radius));
+                return Err((0, input, error));
+            }
         }
         self.input = input;
-        let mut next_input = self.elements[0].solve(input)?;
-        for element in self.elements.iter_mut().skip(1){
-            next_input = element.solve(next_input)?;
+        let mut next_input = input;
+        for (index, element) in self.elements.iter_mut().enumerate(){
+            next_input = element.solve(next_input).map_err(|error| (index, next_input, error))?;
         }
         Ok(())
     }
+    // sweep input_radius from start_radius to end_radius with input_theta and find where assembly is lost
+    // return None if the scissor can be assembled over the whole range
+    // steps must be positive and tolerance must be positive
+    pub fn find_limit_position(&self, input_theta: f64, start_radius: f64, end_radius: f64, steps: usize, tolerance: f64) -> Result<Option<ScissorLimitPosition>, std::io::Error>{
+        if steps == 0 || tolerance.is_nan() || tolerance <= 0.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("steps {} and tolerance {} must be positive", steps, tolerance)));
+        }
+        let can_assemble = |radius: f64| self.clone().solve(variable_vector::VariableFPolVec2::from(radius, input_theta)).is_ok();
+        if !can_assemble(start_radius){
+            return Err(std::io::Error::other("scissor cannot be assembled at start_radius"));
+        }
+        let (assembled, lost) = match limit_position::find_limit(start_radius, end_radius, steps, tolerance, can_assemble){
+            Some(bracket) => bracket,
+            None => return Ok(None),
+        };
+        if let Err((element_index, element_input, _)) = self.clone().solve_elements(variable_vector::VariableFPolVec2::from(lost, input_theta)){
+            let dimension = &self.elements[element_index];
+            Ok(Some(ScissorLimitPosition{
+                input_radius: assembled,
+                element_index,
+                stretched: element_input.radius > dimension.c.radius + dimension.d.radius,
+            }))
+        }else{
+            Err(std::io::Error::other("limit position is not reproducible"))
+        }
+    }
     pub fn draw(&self, plotter_backend : &mut BitMapBackend, scale: f64, color: &RGBColor) -> Result<(), std::io::Error>{
         let size = plotter_backend.get_size();
-        let plot_origin = (0 as i32 / 2, size.1 as i32);
-
-        let mut next_vec_origin = (0.0, 0.0);
-        let mut next_vec_input = 
-        if let variable_vector::VariableFRecVec2{x: VariableF::Fixed(x),y: VariableF::Fixed(y)} = self.input.to_rec(){
-            (x, y)
-        }else{
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "input must be fixed"));
-        };
-        for (index, element) in self.elements.iter().enumerate(){
-            if let (
-                variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(ax), y: VariableF::<f64>::Fixed(ay)},
-                variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(bx), y: VariableF::<f64>::Fixed(by)}
-            ) = (element.a.to_rec(), element.b.to_rec()) {
-                plotter_backend.draw_line(
-                    (plot_origin.0 + (next_vec_origin.0 * scale) as i32, plot_origin.1 - (next_vec_origin.1 * scale) as i32),
-                    (plot_origin.0 + ((next_vec_origin.0 + ax) * scale) as i32, plot_origin.1 - ((next_vec_origin.1 + ay) * scale) as i32),
-                    color
-                ).unwrap();
-                plotter_backend.draw_line(
-                    (plot_origin.0 + ((next_vec_origin.0 + next_vec_input.0) * scale) as i32, plot_origin.1 - ((next_vec_origin.1 + next_vec_input.1) * scale) as i32),
-                    (plot_origin.0 + ((next_vec_origin.0 + next_vec_input.0 + bx) * scale) as i32, plot_origin.1 - ((next_vec_origin.1 + next_vec_input.1 + by) * scale) as i32),
-                    color
-                ).unwrap();
-                next_vec_origin.0 = next_vec_origin.0 + next_vec_input.0 + bx;
-                next_vec_origin.1 = next_vec_origin.1 + next_vec_input.1 + by;
-                next_vec_input.0 = ax - next_vec_input.0 - bx;
-                next_vec_input.1 = ay - next_vec_input.1 - by;
-            }else{
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("not fixed at element {}", index)));
+        let plot_origin = (0, size.1 as i32);
+        let to_plot = |point: (f64, f64)| (plot_origin.0 + (point.0 * scale) as i32, plot_origin.1 - (point.1 * scale) as i32);
+        for element_joints in self.get_joints()?.chunks(JOINTS_PER_ELEMENT){
+            let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| element_joints[i].position());
+            // draw through the pivot so that angulated bars are drawn bent
+            for (from, to) in [(a_origin, pivot), (pivot, a_end), (b_origin, pivot), (pivot, b_end)]{
+                plotter_backend.draw_line(to_plot(from), to_plot(to), color)
+                    .map_err(|error| std::io::Error::other(error.to_string()))?;
             }
         }
         Ok(())
     }
+    pub fn get_element_count(&self) -> usize{
+        self.elements.len()
+    }
     pub fn get_endpoint(&self) -> Result<(f64,f64), std::io::Error>{
+        let joints = self.get_joints()?;
+        Ok(joints[joints.len() - 1].position())
+    }
+    // every joint of the solved scissor, JOINTS_PER_ELEMENT joints for each element
+    // order in the element is a origin, b origin, pivot, a end, b end
+    pub fn get_joints(&self) -> Result<Vec<ScissorJoint>, std::io::Error>{
         // check input is fixed
         let mut next_vec_origin = (0.0, 0.0);
         let mut next_vec_input = 
         if let variable_vector::VariableFRecVec2{x: VariableF::Fixed(x),y: VariableF::Fixed(y)} = self.input.to_rec(){
             (x, y)
         }else{
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "input must be fixed"));
+            return Err(std::io::Error::other("input must be fixed"));
         };
+        let mut joints = Vec::with_capacity(self.elements.len() * JOINTS_PER_ELEMENT);
         // check all elements are fixed
         for (index, element) in self.elements.iter().enumerate(){
             if let (
                 variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(ax), y: VariableF::<f64>::Fixed(ay)},
-                variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(bx), y: VariableF::<f64>::Fixed(by)}
-            ) = (element.a.to_rec(), element.b.to_rec()) {
-                next_vec_origin.0 = next_vec_origin.0 + next_vec_input.0 + bx;
-                next_vec_origin.1 = next_vec_origin.1 + next_vec_input.1 + by;
-                next_vec_input.0 = ax - next_vec_input.0 - bx;
-                next_vec_input.1 = ay - next_vec_input.1 - by;
+                variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(bx), y: VariableF::<f64>::Fixed(by)},
+                variable_vector::VariableFRecVec2{x: VariableF::<f64>::Fixed(cx), y: VariableF::<f64>::Fixed(cy)},
+            ) = (element.a.to_rec(), element.b.to_rec(), element.c.to_rec()) {
+                let b_origin = (next_vec_origin.0 + next_vec_input.0, next_vec_origin.1 + next_vec_input.1);
+                let positions = [
+                    next_vec_origin,
+                    b_origin,
+                    (next_vec_origin.0 + cx, next_vec_origin.1 + cy),
+                    (next_vec_origin.0 + ax, next_vec_origin.1 + ay),
+                    (b_origin.0 + bx, b_origin.1 + by),
+                ];
+                for (role, position) in JOINT_ROLES.iter().zip(positions){
+                    joints.push(ScissorJoint{element_index: index, role: *role, x: position.0, y: position.1});
+                }
+                next_vec_origin = positions[4];
+                next_vec_input = (positions[3].0 - positions[4].0, positions[3].1 - positions[4].1);
             }else{
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("not fixed at element {}", index)));
+                return Err(std::io::Error::other(format!("not fixed at element {}", index)));
             }
         }
-        Ok(next_vec_origin)
+        Ok(joints)
+    }
+    // range of input_radius that the scissor can be assembled (min, max)
+    // input_radius is searched in (0, c + d of the first element] by steps
+    // steps must be positive and tolerance must be positive
+    pub fn get_assemblable_range(&self, steps: usize, tolerance: f64) -> Result<(f64, f64), std::io::Error>{
+        if steps == 0 || tolerance.is_nan() || tolerance <= 0.0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("steps {} and tolerance {} must be positive", steps, tolerance)));
+        }
+        let first = self.elements.first().ok_or(std::io::Error::other("scissor has no element"))?;
+        let VariableF::Fixed(max_radius) = first.c.radius + first.d.radius else {
+            return Err(std::io::Error::other("length of the first element is not fixed"));
+        };
+        let can_assemble = |radius: f64| self.clone().solve(variable_vector::VariableFPolVec2::from(radius, 0.0)).is_ok();
+        let start = (1..=steps)
+            .map(|i| max_radius * i as f64 / steps as f64)
+            .find(|&radius| can_assemble(radius))
+            .ok_or(std::io::Error::other("scissor cannot be assembled at any input"))?;
+        let min = match limit_position::find_limit(start, 0.0, steps, tolerance, can_assemble){
+            Some((assembled, _)) => assembled,
+            None => 0.0,
+        };
+        let max = match limit_position::find_limit(start, max_radius, steps, tolerance, can_assemble){
+            Some((assembled, _)) => assembled,
+            None => max_radius,
+        };
+        Ok((min, max))
     }
-}
\ No newline at end of file
+    // every input_radius in the assemblable range where y of the endpoint is height, in ascending order
+    // empty if the height is unreachable
+    pub fn find_inputs_for_height(&self, height: f64, input_theta: f64, steps: usize, tolerance: f64) -> Result<Vec<f64>, std::io::Error>{
+        if !height.is_finite() || !input_theta.is_finite() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("height {} or input_theta {} is not finite", height, input_theta)));
+        }
+        let (min, max) = self.get_assemblable_range(steps, tolerance)?;
+        Ok(optimize::find_roots(|radius| {
+            let mut scissor = self.clone();
+            scissor.solve(variable_vector::VariableFPolVec2::from(radius, input_theta)).ok()?;
+            Some(scissor.get_endpoint().ok()?.1 - height)
+        }, min, max, steps, tolerance))
+    }
+    // inverse kinematics for the height (y of the endpoint)
+    // if several inputs reach the height, the smallest input_radius (first of find_inputs_for_height) is chosen
+    // return input_radius and leave the scissor solved at it
+    pub fn solve_for_height(&mut self, height: f64, input_theta: f64, steps: usize, tolerance: f64) -> Result<f64, std::io::Error>{
+        let radius = *self.find_inputs_for_height(height, input_theta, steps, tolerance)?.first()
+            .ok_or(std::io::Error::other(format!("height {} is unreachable", height)))?;
+        self.solve(variable_vector::VariableFPolVec2::from(radius, input_theta))?;
+        Ok(radius)
+    }
+    // inverse kinematics for the endpoint
+    // whole scissor rotates with input theta, so input_radius is decided by the distance to the target
+    // if several input_radius give the distance, the smallest one is chosen
+    // return input and leave the scissor solved at it
+    pub fn solve_for_endpoint(&mut self, target: (f64, f64), steps: usize, tolerance: f64) -> Result<variable_vector::VariableFPolVec2, std::io::Error>{
+        if !target.0.is_finite() || !target.1.is_finite() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("target ({}, {}) is not finite", target.0, target.1)));
+        }
+        let (min, max) = self.get_assemblable_range(steps, tolerance)?;
+        let distance = (target.0 * target.0 + target.1 * target.1).sqrt();
+        let radius = optimize::find_root(|radius| {
+            let mut scissor = self.clone();
+            scissor.solve(variable_vector::VariableFPolVec2::from(radius, 0.0)).ok()?;
+            let (x, y) = scissor.get_endpoint().ok()?;
+            Some((x * x + y * y).sqrt() - distance)
+        }, min, max, steps, tolerance)
+            .ok_or(std::io::Error::other(format!("endpoint ({}, {}) is unreachable", target.0, target.1)))?;
+        self.solve(variable_vector::VariableFPolVec2::from(radius, 0.0))?;
+        let (x, y) = self.get_endpoint()?;
+        let input = variable_vector::VariableFPolVec2::from(radius, target.1.atan2(target.0) - y.atan2(x));
+        self.solve(input)?;
+        Ok(input)
+    }
+    pub fn get_transmission_angles(&self) -> Result<Vec<f64>, std::io::Error>{
+        self.elements.iter().map(|element| element.get_transmission_angle()).collect()
+    }
+    // mechanical advantage is calculated by central difference of input_radius
+    // if one side cannot be assembled, one sided difference is used
+    pub fn get_mechanical_advantage(&self) -> Result<f64, std::io::Error>{
+        let (radius, theta) = if let (VariableF::Fixed(r), VariableF::Fixed(t)) = (self.input.radius, self.input.theta){
+            (r, t)
+        }else{
+            return Err(std::io::Error::other("input must be fixed"));
+        };
+        let step = radius * 1e-6;
+        let endpoint_at = |r: f64| -> Option<(f64, f64)>{
+            let mut scissor = self.clone();
+            scissor.solve(variable_vector::VariableFPolVec2::from(r, theta)).ok()?;
+            scissor.get_endpoint().ok()
+        };
+        let (from, to, input_diff) = match (endpoint_at(radius - step), endpoint_at(radius + step)){
+            (Some(from), Some(to)) => (from, to, 2.0 * step),
+            (None, Some(to)) => (self.get_endpoint()?, to, step),
+            (Some(from), None) => (from, self.get_endpoint()?, step),
+            (None, None) => return Err(std::io::Error::other("cannot solve around the input")),
+        };
+        let output_diff = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        Ok(input_diff / output_diff)
+    }
+    // solve and report transmission angles and mechanical advantage
+    // min_transmission_angle is the threshold in radian (e.g. 40deg = 0.698)
+    pub fn solve_with_report(&mut self, input: variable_vector::VariableFPolVec2, min_transmission_angle: f64) -> Result<ScissorSolveReport, std::io::Error>{
+        self.solve(input)?;
+        let transmission_angles = self.get_transmission_angles()?;
+        let poor_transmission = transmission_angles.iter().enumerate()
+            .filter(|(_, &angle)| angle < min_transmission_angle || angle > PI - min_transmission_angle)
+            .map(|(index, _)| index)
+            .collect();
+        Ok(ScissorSolveReport{
+            endpoint: self.get_endpoint()?,
+            transmission_angles,
+            mechanical_advantage: self.get_mechanical_advantage()?,
+            poor_transmission,
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scissor() -> Scissor {
+        Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 3]).unwrap()
+    }
+
+    #[test]
+    fn solve_for_height_reproduces_forward_solve() {
+        let mut forward = scissor();
+        forward.solve(variable_vector::VariableFPolVec2::from(1.2, 0.0)).unwrap();
+        let height = forward.get_endpoint().unwrap().1;
+        let mut inverse = scissor();
+        assert_eq!(inverse.find_inputs_for_height(height, 0.0, 100, 1e-12).unwrap().len(), 1);
+        let radius = inverse.solve_for_height(height, 0.0, 100, 1e-12).unwrap();
+        assert!((radius - 1.2).abs() < 1e-9, "{}", radius);
+        assert!((inverse.get_endpoint().unwrap().1 - height).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_for_endpoint_reproduces_forward_solve() {
+        let mut forward = scissor();
+        forward.solve(variable_vector::VariableFPolVec2::from(0.8, 0.3)).unwrap();
+        let target = forward.get_endpoint().unwrap();
+        let mut inverse = scissor();
+        inverse.solve_for_endpoint(target, 100, 1e-12).unwrap();
+        let endpoint = inverse.get_endpoint().unwrap();
+        assert!((endpoint.0 - target.0).abs() < 1e-9 && (endpoint.1 - target.1).abs() < 1e-9, "{:?} != {:?}", endpoint, target);
+    }
+
+    #[test]
+    fn unreachable_height_is_error() {
+        let mut scissor = scissor();
+        assert!(scissor.find_inputs_for_height(100.0, 0.0, 100, 1e-12).unwrap().is_empty());
+        assert!(scissor.solve_for_height(100.0, 0.0, 100, 1e-12).is_err());
+        assert!(scissor.solve_for_height(f64::NAN, 0.0, 100, 1e-12).is_err());
+    }
+
+    #[test]
+    fn zero_input_radius_is_invalid() {
+        // c == d, so the direction of c and d would be NaN
+        let error = scissor().solve(variable_vector::VariableFPolVec2::from(0.0, 0.0)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(scissor().solve(variable_vector::VariableFPolVec2::from(-1.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn assemblable_range_of_symmetric_scissor() {
+        let (min, max) = scissor().get_assemblable_range(100, 1e-9).unwrap();
+        assert!(min > 0.0 && min < 1e-8, "{}", min);
+        assert_eq!(max, 2.0);
+        assert!(scissor().get_assemblable_range(0, 1e-9).is_err());
+        assert!(scissor().get_assemblable_range(100, 0.0).is_err());
+    }
+
+    #[test]
+    fn report_matches_symmetric_scissor() {
+        // each element is an isosceles triangle of c, d and the input, so bars meet at 2 * asin(r / 2)
+        // and the chain extends by 2 * sqrt(1 - (r / 2)^2) per element
+        let radius = 1.0;
+        let mut reported = scissor();
+        let report = reported.solve_with_report(variable_vector::VariableFPolVec2::from(radius, 0.0), 65f64.to_radians()).unwrap();
+        let expected_angle = 2.0 * (radius / 2.0f64).asin();
+        assert_eq!(report.transmission_angles.len(), 3);
+        for &angle in report.transmission_angles.iter() {
+            assert!((angle - expected_angle).abs() < 1e-9 || (angle - (PI - expected_angle)).abs() < 1e-9, "{} != {}", angle, expected_angle);
+        }
+        // 60deg and 120deg are both out of [65deg, 115deg]
+        assert_eq!(report.poor_transmission, vec![0, 1, 2]);
+        assert_eq!(report.endpoint, reported.get_endpoint().unwrap());
+        // tip force / input force is d(input) / d(extension)
+        let half = radius / 2.0;
+        let expected_advantage = (1.0 - half * half).sqrt() / (3.0 * half);
+        assert!((report.mechanical_advantage - expected_advantage).abs() < 1e-6, "{} != {}", report.mechanical_advantage, expected_advantage);
+        // finite difference over a wider step agrees as well
+        let extension = |radius: f64| {
+            let mut scissor = scissor();
+            scissor.solve(variable_vector::VariableFPolVec2::from(radius, 0.0)).unwrap();
+            scissor.get_endpoint().unwrap().1
+        };
+        let finite_difference = 2e-3 / (extension(radius + 1e-3) - extension(radius - 1e-3)).abs();
+        assert!((report.mechanical_advantage - finite_difference).abs() < 1e-5);
+        let report = reported.solve_with_report(variable_vector::VariableFPolVec2::from(radius, 0.0), 40f64.to_radians()).unwrap();
+        assert!(report.poor_transmission.is_empty());
+    }
+
+    #[test]
+    fn limit_position_of_symmetric_scissor() {
+        // every element is stretched at once when the input reaches c + d = 2
+        let limit = scissor().find_limit_position(0.0, 1.0, 3.0, 100, 1e-12).unwrap().unwrap();
+        assert!((limit.input_radius - 2.0).abs() < 1e-9, "{}", limit.input_radius);
+        assert!(limit.input_radius <= 2.0);
+        assert_eq!(limit.element_index, 0);
+        assert!(limit.stretched);
+        assert!(scissor().find_limit_position(0.0, 1.0, 1.5, 100, 1e-12).unwrap().is_none());
+        assert!(scissor().find_limit_position(0.0, 3.0, 1.0, 100, 1e-12).is_err());
+        assert_eq!(scissor().find_limit_position(0.0, 1.0, 3.0, 0, 1e-12).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(scissor().find_limit_position(0.0, 1.0, 3.0, 100, 0.0).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(scissor().find_limit_position(0.0, 1.0, 3.0, 100, f64::NAN).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn kinked_bars_keep_their_segments() {
+        let dimension = AngulatedScissorDimension{a: 2.0, b: 2.0, c: 1.2, d: 0.9, kink_a: 0.3, kink_b: -0.2};
+        let mut angulated = Scissor::new_angulated(vec![dimension; 3]).unwrap();
+        angulated.solve(variable_vector::VariableFPolVec2::from(1.0, 0.2)).unwrap();
+        let length = |p: (f64, f64), q: (f64, f64)| ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2)).sqrt();
+        let angle = |p: (f64, f64), q: (f64, f64)| (q.1 - p.1).atan2(q.0 - p.0);
+        let wrap = |angle: f64| (angle + PI).rem_euclid(2.0 * PI) - PI;
+        for element_joints in angulated.get_joints().unwrap().chunks(JOINTS_PER_ELEMENT) {
+            let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| element_joints[i].position());
+            assert!((length(a_origin, pivot) - dimension.c).abs() < 1e-9);
+            assert!((length(pivot, a_end) - (dimension.a - dimension.c)).abs() < 1e-9);
+            assert!((length(b_origin, pivot) - dimension.d).abs() < 1e-9);
+            assert!((length(pivot, b_end) - (dimension.b - dimension.d)).abs() < 1e-9);
+            // outer part is bent from the inner part by the kink
+            assert!(wrap(angle(pivot, a_end) - angle(a_origin, pivot) - dimension.kink_a).abs() < 1e-9);
+            assert!(wrap(angle(pivot, b_end) - angle(b_origin, pivot) - dimension.kink_b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn zero_kink_is_straight_scissor() {
+        let dimensions = vec![ScissorDimension{a: 2.0, b: 1.8, c: 1.2, d: 0.9}; 3];
+        let input = variable_vector::VariableFPolVec2::from(1.0, 0.2);
+        let mut straight = Scissor::new(dimensions.clone()).unwrap();
+        straight.solve(input).unwrap();
+        let mut angulated = Scissor::new_angulated(dimensions.into_iter().map(AngulatedScissorDimension::from).collect()).unwrap();
+        angulated.solve(input).unwrap();
+        for (straight, angulated) in straight.get_joints().unwrap().iter().zip(angulated.get_joints().unwrap()) {
+            assert_eq!(straight.role, angulated.role);
+            assert!((straight.x - angulated.x).abs() < 1e-12 && (straight.y - angulated.y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn dimension_array_element_checks_index_and_null() {
+        let code = |error: LinkageSimError| error as isize;
+        let initial = ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0};
+        let value = ScissorDimension{a: 3.0, b: 2.5, c: 1.5, d: 1.0};
+        let untouched = ScissorDimension{a: -1.0, b: -1.0, c: -1.0, d: -1.0};
+        let mut array = [initial; 3];
+        let mut out = untouched;
+        let is = |dimension: ScissorDimension, expected: ScissorDimension| {
+            [dimension.a, dimension.b, dimension.c, dimension.d] == [expected.a, expected.b, expected.c, expected.d]
+        };
+        unsafe {
+            assert_eq!(set_scissor_dimension_array_element(array.as_mut_ptr(), 2, 1, value), code(LinkageSimError::Ok));
+            assert_eq!(get_scissor_dimension_array_element(array.as_ptr(), 2, 1, &mut out), code(LinkageSimError::Ok));
+            assert!(is(out, value));
+
+            // index 2 is outside of the first two elements even though the array has three
+            out = untouched;
+            assert_eq!(get_scissor_dimension_array_element(array.as_ptr(), 2, 2, &mut out), code(LinkageSimError::IndexOutOfRange));
+            assert!(is(out, untouched));
+            assert_eq!(set_scissor_dimension_array_element(array.as_mut_ptr(), 2, 2, value), code(LinkageSimError::IndexOutOfRange));
+            assert!(is(array[2], initial));
+            assert_eq!(get_scissor_dimension_array_element(array.as_ptr(), 0, 0, &mut out), code(LinkageSimError::IndexOutOfRange));
+            assert!(is(out, untouched));
+
+            assert_eq!(get_scissor_dimension_array_element(std::ptr::null(), 2, 0, &mut out), code(LinkageSimError::NullPointer));
+            assert!(is(out, untouched));
+            assert_eq!(get_scissor_dimension_array_element(array.as_ptr(), 2, 0, std::ptr::null_mut()), code(LinkageSimError::NullPointer));
+            assert_eq!(set_scissor_dimension_array_element(std::ptr::null_mut(), 2, 0, value), code(LinkageSimError::NullPointer));
+        }
+    }
+
+    #[test]
+    fn handle_api_matches_scissor() {
+        let mut dimensions = [ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 3];
+        let mut expected = scissor();
+        expected.solve(variable_vector::VariableFPolVec2::from(1.2, 0.3)).unwrap();
+        let expected_joints = expected.get_joints().unwrap();
+        let expected_endpoint = expected.get_endpoint().unwrap();
+        let assert_joints = |joints: &[ScissorJoint]| {
+            assert_eq!(joints.len(), expected_joints.len());
+            for (joint, expected) in joints.iter().zip(&expected_joints) {
+                assert_eq!((joint.element_index, joint.role), (expected.element_index, expected.role));
+                assert!((joint.x - expected.x).abs() < 1e-12 && (joint.y - expected.y).abs() < 1e-12);
+            }
+        };
+        let code = |error: LinkageSimError| error as isize;
+        unsafe {
+            let scissor = scissor_new(dimensions.as_ptr(), 3);
+            assert!(!scissor.is_null());
+            let mut num_joints = 0;
+            let mut buffer = [ScissorJoint{element_index: 0, role: ScissorJointRole::AOrigin, x: 0.0, y: 0.0}; 15];
+            assert_eq!(scissor_get_joints(scissor, buffer.as_mut_ptr(), 15, &mut num_joints), code(LinkageSimError::NotSolved));
+            assert_eq!(scissor_solve(scissor, 1.2, 0.3), code(LinkageSimError::Ok));
+            assert_eq!(scissor_get_joints(scissor, buffer.as_mut_ptr(), 14, &mut num_joints), code(LinkageSimError::BufferTooSmall));
+            assert_eq!(num_joints, 15);
+            assert_eq!(scissor_get_joints(scissor, buffer.as_mut_ptr(), 15, &mut num_joints), code(LinkageSimError::Ok));
+            assert_joints(&buffer);
+
+            // failed solve keeps the previous solution
+            assert_eq!(scissor_solve(scissor, 2.5, 0.3), code(LinkageSimError::CannotAssemble));
+            assert_eq!(scissor_solve(scissor, f64::NAN, 0.3), code(LinkageSimError::InvalidInput));
+            let (mut x, mut y) = (0.0, 0.0);
+            assert_eq!(scissor_get_endpoint(scissor, &mut x, &mut y), code(LinkageSimError::Ok));
+            assert_eq!((x, y), expected_endpoint);
+
+            let radii = [1.2, 2.5, f64::NAN];
+            let thetas = [0.3; 3];
+            let mut samples = [scissor_sweep_sample{error: -1, x: 0.0, y: 0.0}; 3];
+            let mut joints = [ScissorJoint{element_index: 0, role: ScissorJointRole::AOrigin, x: 0.0, y: 0.0}; 45];
+            assert_eq!(scissor_solve_sweep(scissor, radii.as_ptr(), thetas.as_ptr(), 3, samples.as_mut_ptr(), joints.as_mut_ptr(), 44), code(LinkageSimError::BufferTooSmall));
+            assert_eq!(scissor_solve_sweep(scissor, radii.as_ptr(), thetas.as_ptr(), 3, samples.as_mut_ptr(), joints.as_mut_ptr(), 45), code(LinkageSimError::Ok));
+            assert_eq!(samples.map(|sample| sample.error), [code(LinkageSimError::Ok), code(LinkageSimError::CannotAssemble), code(LinkageSimError::InvalidInput)]);
+            assert_eq!((samples[0].x, samples[0].y), expected_endpoint);
+            assert!(samples[1..].iter().all(|sample| sample.x.is_nan() && sample.y.is_nan()));
+            assert_joints(&joints[..15]);
+            scissor_free(scissor);
+
+            let null = std::ptr::null_mut();
+            assert_eq!(scissor_solve(null, 1.2, 0.3), code(LinkageSimError::NullPointer));
+            assert_eq!(scissor_get_endpoint(null, &mut x, &mut y), code(LinkageSimError::NullPointer));
+            assert_eq!(scissor_get_joints(null, buffer.as_mut_ptr(), 15, &mut num_joints), code(LinkageSimError::NullPointer));
+            assert_eq!(scissor_solve_sweep(null, radii.as_ptr(), thetas.as_ptr(), 3, samples.as_mut_ptr(), std::ptr::null_mut(), 0), code(LinkageSimError::NullPointer));
+            scissor_free(null);
+
+            assert!(scissor_new(std::ptr::null(), 3).is_null());
+            assert!(scissor_new(dimensions.as_ptr(), 0).is_null());
+            // pivot outside of the bar
+            dimensions[1].c = 3.0;
+            assert!(scissor_new(dimensions.as_ptr(), 3).is_null());
+        }
+    }
+}
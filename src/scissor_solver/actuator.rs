@@ -0,0 +1,154 @@
+use std::io::{Error, ErrorKind};
+
+use super::{Scissor, ScissorJoint, ScissorJointRole, JOINT_ROLES, JOINTS_PER_ELEMENT};
+use crate::mech_solver::optimize;
+use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+// joint of the scissor pointed by element index and role
+#[derive(Debug, Clone, Copy)]
+pub struct ScissorJointRef{
+    pub element_index: usize,
+    pub role: ScissorJointRole,
+}
+
+// linear actuator (e.g. hydraulic cylinder) mounted between two joints
+#[derive(Debug, Clone, Copy)]
+pub struct LinearActuator{
+    pub from: ScissorJointRef,
+    pub to: ScissorJointRef,
+}
+
+// ActuatorStroke is actuator length over a deployment range
+// stroke is max_length - min_length
+#[derive(Debug, Clone, Copy)]
+pub struct ActuatorStroke{
+    pub min_length: f64,
+    pub max_length: f64,
+    pub stroke: f64,
+}
+
+impl Scissor{
+    pub(super) fn find_joint(joints: &[ScissorJoint], joint: ScissorJointRef) -> Result<(f64, f64), Error>{
+        let role_index = JOINT_ROLES.iter().position(|role| *role == joint.role).unwrap();
+        joints.get(joint.element_index * JOINTS_PER_ELEMENT + role_index)
+            .map(|joint| joint.position())
+            .ok_or(Error::other(format!("element {} does not exist", joint.element_index)))
+    }
+    // length of the actuator in the solved scissor
+    pub fn get_actuator_length(&self, actuator: &LinearActuator) -> Result<f64, Error>{
+        let joints = self.get_joints()?;
+        let from = Self::find_joint(&joints, actuator.from)?;
+        let to = Self::find_joint(&joints, actuator.to)?;
+        Ok(((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt())
+    }
+    // every input_radius in the assemblable range where the actuator has the length, in ascending order
+    // actuator length is not monotonic for curved chains, so one length can have several inputs (assembly branches)
+    // empty if the length is unreachable
+    pub fn find_inputs_by_actuator(&self, actuator: &LinearActuator, length: f64, input_theta: f64, steps: usize, tolerance: f64) -> Result<Vec<f64>, Error>{
+        if !length.is_finite() || !input_theta.is_finite() {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("length {} or input_theta {} is not finite", length, input_theta)));
+        }
+        let (min, max) = self.get_assemblable_range(steps, tolerance)?;
+        Ok(optimize::find_roots(|radius| {
+            let mut scissor = self.clone();
+            scissor.solve(VariableFPolVec2::from(radius, input_theta)).ok()?;
+            Some(scissor.get_actuator_length(actuator).ok()? - length)
+        }, min, max, steps, tolerance))
+    }
+    // solve the scissor so that the actuator has the length
+    // branch : index in find_inputs_by_actuator, 0 is the smallest input_radius
+    // return input_radius and leave the scissor solved at it
+    pub fn solve_by_actuator(&mut self, actuator: &LinearActuator, length: f64, input_theta: f64, branch: usize, steps: usize, tolerance: f64) -> Result<f64, Error>{
+        let inputs = self.find_inputs_by_actuator(actuator, length, input_theta, steps, tolerance)?;
+        let radius = match (inputs.get(branch), inputs.len()){
+            (Some(&radius), _) => radius,
+            (None, 0) => return Err(Error::other(format!("actuator length {} is unreachable", length))),
+            (None, count) => return Err(Error::other(format!("actuator length {} has only {} branches", length, count))),
+        };
+        self.solve(VariableFPolVec2::from(radius, input_theta))?;
+        Ok(radius)
+    }
+    // actuator length needed to deploy the scissor from start_radius to end_radius with input_theta
+    // every sample must be assembled
+    pub fn get_actuator_stroke(&self, actuator: &LinearActuator, start_radius: f64, end_radius: f64, input_theta: f64, steps: usize) -> Result<ActuatorStroke, Error>{
+        if !input_theta.is_finite() {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("input_theta {} is not finite", input_theta)));
+        }
+        let mut min_length = f64::MAX;
+        let mut max_length = f64::MIN;
+        for i in 0..=steps{
+            let radius = start_radius + (end_radius - start_radius) * i as f64 / steps.max(1) as f64;
+            let mut scissor = self.clone();
+            scissor.solve(VariableFPolVec2::from(radius, input_theta))?;
+            let length = scissor.get_actuator_length(actuator)?;
+            min_length = min_length.min(length);
+            max_length = max_length.max(length);
+        }
+        Ok(ActuatorStroke{
+            min_length,
+            max_length,
+            stroke: max_length - min_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scissor_solver::ScissorDimension;
+
+    // pivot is off the center, so the chain curves and the actuator length has a maximum in the range
+    fn curved() -> Scissor {
+        Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.5, d: 0.5}; 3]).unwrap()
+    }
+
+    const ACTUATOR: LinearActuator = LinearActuator{
+        from: ScissorJointRef{element_index: 0, role: ScissorJointRole::AOrigin},
+        to: ScissorJointRef{element_index: 2, role: ScissorJointRole::AEnd},
+    };
+
+    #[test]
+    fn every_branch_has_the_length() {
+        let inputs = curved().find_inputs_by_actuator(&ACTUATOR, 2.5, 0.0, 100, 1e-12).unwrap();
+        assert_eq!(inputs.len(), 2, "{:?}", inputs);
+        assert!(inputs[0] < inputs[1]);
+        for (branch, &input) in inputs.iter().enumerate() {
+            let mut scissor = curved();
+            let radius = scissor.solve_by_actuator(&ACTUATOR, 2.5, 0.0, branch, 100, 1e-12).unwrap();
+            assert_eq!(radius, input);
+            assert!((scissor.get_actuator_length(&ACTUATOR).unwrap() - 2.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn missing_branch_is_error() {
+        assert!(curved().solve_by_actuator(&ACTUATOR, 2.5, 0.0, 2, 100, 1e-12).is_err());
+        assert!(curved().solve_by_actuator(&ACTUATOR, 10.0, 0.0, 0, 100, 1e-12).is_err());
+        assert!(curved().solve_by_actuator(&ACTUATOR, f64::NAN, 0.0, 0, 100, 1e-12).is_err());
+    }
+
+    #[test]
+    fn stroke_covers_every_sampled_length() {
+        let input_theta = 0.7;
+        let stroke = curved().get_actuator_stroke(&ACTUATOR, 1.05, 1.95, input_theta, 90).unwrap();
+        let lengths: Vec<f64> = (0..=90).map(|i| {
+            let mut scissor = curved();
+            scissor.solve(VariableFPolVec2::from(1.05 + 0.9 * i as f64 / 90.0, input_theta)).unwrap();
+            scissor.get_actuator_length(&ACTUATOR).unwrap()
+        }).collect();
+        assert_eq!(stroke.min_length, lengths.iter().copied().fold(f64::MAX, f64::min));
+        assert_eq!(stroke.max_length, lengths.iter().copied().fold(f64::MIN, f64::max));
+        assert_eq!(stroke.stroke, stroke.max_length - stroke.min_length);
+        // length 2.5 has two branches in the range, so the length passes a maximum above it
+        assert!(stroke.max_length > 2.5 && stroke.max_length > lengths[0] && stroke.max_length > lengths[90]);
+        // actuator is between joints of the chain, so rotating the whole chain does not change the stroke
+        let unrotated = curved().get_actuator_stroke(&ACTUATOR, 1.05, 1.95, 0.0, 90).unwrap();
+        assert!((unrotated.stroke - stroke.stroke).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stroke_out_of_range_is_error() {
+        assert!(curved().get_actuator_stroke(&ACTUATOR, 1.05, 2.5, 0.0, 100).is_err());
+        assert!(curved().get_actuator_stroke(&ACTUATOR, 1.05, 1.95, f64::NAN, 100).is_err());
+    }
+}
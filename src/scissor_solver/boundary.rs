@@ -0,0 +1,159 @@
+use thiserror::Error;
+
+use super::{Scissor, ScissorJointRole, actuator::ScissorJointRef};
+use crate::mech_solver::optimize;
+use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+// support at the start of the scissor, a origin of the first element is always pinned at the origin
+pub enum ScissorStartSupport{
+    // b origin slides on the line from the origin with angle input_theta (unknown : input_radius)
+    Sliding{input_theta: f64},
+    // b origin is free and the scissor rotates around the origin (unknown : input_radius and input_theta)
+    Pinned,
+}
+
+// support at the end of the scissor, joint is the joint of the last element
+pub enum ScissorEndSupport{
+    // joint is pinned at point
+    Pinned{joint: ScissorJointRole, point: (f64, f64)},
+    // joint slides on the line through point with angle direction_theta
+    Sliding{joint: ScissorJointRole, point: (f64, f64), direction_theta: f64},
+}
+
+#[derive(Error, Debug)]
+pub enum BoundaryErr{
+    #[error("Too much constraint. End support cannot be satisfied with sliding start support, pin the start instead.")]
+    OverConstrained,
+    #[error("Constraint shortage. Scissor with pinned start and sliding end is still movable, give input_theta.")]
+    UnderConstrained,
+    #[error("No input satisfies the end support.")]
+    NoSolution,
+    #[error(transparent)]
+    Solve(#[from] std::io::Error),
+}
+
+impl Scissor{
+    // solve the scissor whose both ends are supported
+    // return input and leave the scissor solved at it
+    pub fn solve_with_supports(&mut self, start: ScissorStartSupport, end: ScissorEndSupport, steps: usize, tolerance: f64) -> Result<VariableFPolVec2, BoundaryErr>{
+        let last = self.elements.len() - 1;
+        match (start, end){
+            (ScissorStartSupport::Sliding{..}, ScissorEndSupport::Pinned{..}) => Err(BoundaryErr::OverConstrained),
+            (ScissorStartSupport::Pinned, ScissorEndSupport::Sliding{..}) => Err(BoundaryErr::UnderConstrained),
+            (ScissorStartSupport::Sliding{input_theta}, ScissorEndSupport::Sliding{joint, point, direction_theta}) => {
+                let joint = ScissorJointRef{element_index: last, role: joint};
+                let (min, max) = self.get_assemblable_range(steps, tolerance)?;
+                let radius = optimize::find_root(|radius| {
+                    let mut scissor = self.clone();
+                    scissor.solve(VariableFPolVec2::from(radius, input_theta)).ok()?;
+                    let position = Scissor::find_joint(&scissor.get_joints().ok()?, joint).ok()?;
+                    // signed distance from the joint to the sliding line
+                    Some(direction_theta.cos() * (position.1 - point.1) - direction_theta.sin() * (position.0 - point.0))
+                }, min, max, steps, tolerance).ok_or(BoundaryErr::NoSolution)?;
+                let input = VariableFPolVec2::from(radius, input_theta);
+                self.solve(input)?;
+                Ok(input)
+            },
+            (ScissorStartSupport::Pinned, ScissorEndSupport::Pinned{joint, point}) => {
+                // whole scissor rotates with input theta, so input_radius is decided by the distance to the point
+                let joint = ScissorJointRef{element_index: last, role: joint};
+                let (min, max) = self.get_assemblable_range(steps, tolerance)?;
+                let distance = (point.0 * point.0 + point.1 * point.1).sqrt();
+                let radius = optimize::find_root(|radius| {
+                    let mut scissor = self.clone();
+                    scissor.solve(VariableFPolVec2::from(radius, 0.0)).ok()?;
+                    let position = Scissor::find_joint(&scissor.get_joints().ok()?, joint).ok()?;
+                    Some((position.0 * position.0 + position.1 * position.1).sqrt() - distance)
+                }, min, max, steps, tolerance).ok_or(BoundaryErr::NoSolution)?;
+                self.solve(VariableFPolVec2::from(radius, 0.0))?;
+                let position = Scissor::find_joint(&self.get_joints()?, joint)?;
+                let input = VariableFPolVec2::from(radius, point.1.atan2(point.0) - position.1.atan2(position.0));
+                self.solve(input)?;
+                Ok(input)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scissor_solver::ScissorDimension;
+    use crate::mech_solver::triangle_solver::variable_vector::VariableF;
+
+    fn scissor() -> Scissor {
+        Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 3]).unwrap()
+    }
+
+    const END: ScissorJointRef = ScissorJointRef{element_index: 2, role: ScissorJointRole::BEnd};
+
+    fn end_joint(scissor: &Scissor) -> (f64, f64) {
+        Scissor::find_joint(&scissor.get_joints().unwrap(), END).unwrap()
+    }
+
+    #[test]
+    fn sliding_end_lands_on_the_line() {
+        // line through the endpoint of input 1.2, tilted so that it is not parallel to the chain
+        let mut forward = scissor();
+        forward.solve(VariableFPolVec2::from(1.2, 0.0)).unwrap();
+        let point = end_joint(&forward);
+        let direction_theta: f64 = 0.3;
+        let mut supported = scissor();
+        let input = supported.solve_with_supports(
+            ScissorStartSupport::Sliding{input_theta: 0.0},
+            ScissorEndSupport::Sliding{joint: ScissorJointRole::BEnd, point, direction_theta},
+            100, 1e-12,
+        ).unwrap();
+        assert_eq!(input.theta, VariableF::Fixed(0.0));
+        let position = end_joint(&supported);
+        let distance = direction_theta.cos() * (position.1 - point.1) - direction_theta.sin() * (position.0 - point.0);
+        assert!(distance.abs() < 1e-9, "{}", distance);
+    }
+
+    #[test]
+    fn pinned_end_lands_on_the_point() {
+        let mut forward = scissor();
+        forward.solve(VariableFPolVec2::from(1.2, 0.4)).unwrap();
+        let point = end_joint(&forward);
+        let mut supported = scissor();
+        supported.solve_with_supports(
+            ScissorStartSupport::Pinned,
+            ScissorEndSupport::Pinned{joint: ScissorJointRole::BEnd, point},
+            100, 1e-12,
+        ).unwrap();
+        let position = end_joint(&supported);
+        assert!((position.0 - point.0).abs() < 1e-9 && (position.1 - point.1).abs() < 1e-9, "{:?} != {:?}", position, point);
+    }
+
+    #[test]
+    fn unsupported_combination_is_error() {
+        let over = scissor().solve_with_supports(
+            ScissorStartSupport::Sliding{input_theta: 0.0},
+            ScissorEndSupport::Pinned{joint: ScissorJointRole::BEnd, point: (0.0, 3.0)},
+            100, 1e-12,
+        );
+        assert!(matches!(over, Err(BoundaryErr::OverConstrained)));
+        let under = scissor().solve_with_supports(
+            ScissorStartSupport::Pinned,
+            ScissorEndSupport::Sliding{joint: ScissorJointRole::BEnd, point: (0.0, 3.0), direction_theta: 0.0},
+            100, 1e-12,
+        );
+        assert!(matches!(under, Err(BoundaryErr::UnderConstrained)));
+    }
+
+    #[test]
+    fn unreachable_support_is_no_solution() {
+        let sliding = scissor().solve_with_supports(
+            ScissorStartSupport::Sliding{input_theta: 0.0},
+            ScissorEndSupport::Sliding{joint: ScissorJointRole::BEnd, point: (0.0, 100.0), direction_theta: 0.0},
+            100, 1e-12,
+        );
+        assert!(matches!(sliding, Err(BoundaryErr::NoSolution)));
+        let pinned = scissor().solve_with_supports(
+            ScissorStartSupport::Pinned,
+            ScissorEndSupport::Pinned{joint: ScissorJointRole::BEnd, point: (100.0, 100.0)},
+            100, 1e-12,
+        );
+        assert!(matches!(pinned, Err(BoundaryErr::NoSolution)));
+    }
+}
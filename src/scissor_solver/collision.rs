@@ -0,0 +1,202 @@
+use std::io::Error;
+
+use super::{Scissor, JOINTS_PER_ELEMENT};
+use crate::mech_solver::limit_position::{self, SweepRange};
+use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScissorBar{
+    A,
+    B,
+}
+
+// contact between bars treated as capsules (segments with thickness)
+// distance is the distance between center lines of the bars
+#[derive(Debug, Clone, Copy)]
+pub enum ScissorContact{
+    Bars{elements: [usize; 2], bars: [ScissorBar; 2], distance: f64},
+    Ground{element_index: usize, bar: ScissorBar, distance: f64},
+}
+
+pub(crate) type Segment = ((f64, f64), (f64, f64));
+// bar a and bar b of one element, each split at the pivot
+type ElementBars = [(ScissorBar, [Segment; 2]); 2];
+
+fn cross(o: (f64, f64), p: (f64, f64), q: (f64, f64)) -> f64{
+    (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0)
+}
+
+fn point_segment_distance(point: (f64, f64), segment: Segment) -> f64{
+    let (p, q) = segment;
+    let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {0.0} else {(((point.0 - p.0) * dx + (point.1 - p.1) * dy) / len2).clamp(0.0, 1.0)};
+    ((p.0 + t * dx - point.0).powi(2) + (p.1 + t * dy - point.1).powi(2)).sqrt()
+}
+
+pub(crate) fn segment_distance(s1: Segment, s2: Segment) -> f64{
+    let d1 = cross(s1.0, s1.1, s2.0);
+    let d2 = cross(s1.0, s1.1, s2.1);
+    let d3 = cross(s2.0, s2.1, s1.0);
+    let d4 = cross(s2.0, s2.1, s1.1);
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {return 0.0;}
+    point_segment_distance(s1.0, s2)
+        .min(point_segment_distance(s1.1, s2))
+        .min(point_segment_distance(s2.0, s1))
+        .min(point_segment_distance(s2.1, s1))
+}
+
+impl Scissor{
+    // bars of each element as segments, bar is split at the pivot for angulated element
+    fn get_bar_segments(&self) -> Result<Vec<ElementBars>, Error>{
+        Ok(self.get_joints()?.chunks(JOINTS_PER_ELEMENT).map(|element_joints| {
+            let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| element_joints[i].position());
+            [
+                (ScissorBar::A, [(a_origin, pivot), (pivot, a_end)]),
+                (ScissorBar::B, [(b_origin, pivot), (pivot, b_end)]),
+            ]
+        }).collect())
+    }
+    // contacts in the solved scissor
+    // bars of the same or adjacent elements share joints, so only elements 2 or more apart are checked
+    // ground_y : height of the ground, origin joints of the first element stand on it and are not checked
+    pub fn find_collisions(&self, thickness: f64, ground_y: Option<f64>) -> Result<Vec<ScissorContact>, Error>{
+        let elements = self.get_bar_segments()?;
+        let mut contacts = Vec::new();
+        for i in 0..elements.len(){
+            for j in i + 2..elements.len(){
+                for (bar_i, segments_i) in elements[i].iter(){
+                    for (bar_j, segments_j) in elements[j].iter(){
+                        let distance = segments_i.iter()
+                            .flat_map(|&s1| segments_j.iter().map(move |&s2| segment_distance(s1, s2)))
+                            .fold(f64::MAX, f64::min);
+                        if distance < thickness {
+                            contacts.push(ScissorContact::Bars{elements: [i, j], bars: [*bar_i, *bar_j], distance});
+                        }
+                    }
+                }
+            }
+            if let Some(ground_y) = ground_y{
+                for (bar, segments) in elements[i].iter(){
+                    // origin joints of the first element stand on the ground, so only its pivot and end are checked
+                    let skip = if i == 0 {1} else {0};
+                    let distance = [segments[0].0, segments[0].1, segments[1].1].iter().skip(skip)
+                        .map(|point| point.1)
+                        .fold(f64::MAX, f64::min) - ground_y;
+                    if distance < thickness / 2.0 {
+                        contacts.push(ScissorContact::Ground{element_index: i, bar: *bar, distance});
+                    }
+                }
+            }
+        }
+        Ok(contacts)
+    }
+    // sweep input_radius over radius_range and find the first input where contact occurs
+    // return input_radius at the contact and the contacts there
+    // return None if no contact occurs or the scissor cannot be assembled before contact
+    pub fn find_first_collision(&self, thickness: f64, ground_y: Option<f64>, input_theta: f64, radius_range: SweepRange) -> Result<Option<(f64, Vec<ScissorContact>)>, Error>{
+        let SweepRange{start: start_radius, end: end_radius, steps, tolerance} = radius_range;
+        let contacts_at = |radius: f64| -> Option<Vec<ScissorContact>> {
+            let mut scissor = self.clone();
+            scissor.solve(VariableFPolVec2::from(radius, input_theta)).ok()?;
+            scissor.find_collisions(thickness, ground_y).ok()
+        };
+        let is_clear = |radius: f64| contacts_at(radius).is_some_and(|contacts| contacts.is_empty());
+        if !is_clear(start_radius) {
+            return Err(Error::other("scissor has contact or cannot be assembled at start_radius"));
+        }
+        let (clear, lost) = match limit_position::bracket_limit(start_radius, end_radius, steps, is_clear){
+            Some(bracket) => bracket,
+            None => return Ok(None),
+        };
+        // assembly is lost before contact
+        if contacts_at(lost).is_none() {return Ok(None);}
+        // narrow down where the contact starts, scissor must be assembled while bisecting
+        let (_, contact) = limit_position::bisect_limit(clear, lost, tolerance, |radius| {
+            match contacts_at(radius){
+                Some(contacts) => contacts.is_empty(),
+                None => true,
+            }
+        });
+        Ok(contacts_at(contact).map(|contacts| (contact, contacts)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scissor_solver::ScissorDimension;
+
+    // pivot is off the center, so the chain curls up and folds onto itself as input_radius gets small
+    fn folding(num: usize) -> Scissor {
+        Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.4, d: 0.6}; num]).unwrap()
+    }
+
+    fn straight() -> Scissor {
+        Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}]).unwrap()
+    }
+
+    #[test]
+    fn segment_distance_of_crossing_parallel_and_apart_segments() {
+        assert_eq!(segment_distance(((0.0, 0.0), (2.0, 2.0)), ((0.0, 2.0), (2.0, 0.0))), 0.0);
+        assert!((segment_distance(((0.0, 0.0), (2.0, 0.0)), ((0.0, 0.5), (2.0, 0.5))) - 0.5).abs() < 1e-12);
+        // closest points are the end of one and the middle of the other
+        assert!((segment_distance(((0.0, 0.0), (2.0, 0.0)), ((1.0, 3.0), (1.0, 1.0))) - 1.0).abs() < 1e-12);
+        // end to end
+        assert!((segment_distance(((0.0, 0.0), (1.0, 0.0)), ((4.0, 4.0), (4.0, 5.0))) - 5.0).abs() < 1e-12);
+        assert!((point_segment_distance((1.0, 1.0), ((0.0, 0.0), (0.0, 0.0))) - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn folded_chain_has_bar_contact() {
+        let mut scissor = folding(6);
+        scissor.solve(VariableFPolVec2::from(1.0, 0.0)).unwrap();
+        let contacts = scissor.find_collisions(0.1, None).unwrap();
+        assert!(!contacts.is_empty());
+        for contact in contacts {
+            match contact {
+                ScissorContact::Bars{elements, distance, ..} => assert!(elements[1] >= elements[0] + 2 && distance < 0.1),
+                ScissorContact::Ground{..} => panic!("ground is not given"),
+            }
+        }
+        let mut deployed = folding(6);
+        deployed.solve(VariableFPolVec2::from(1.6, 0.0)).unwrap();
+        assert!(deployed.find_collisions(0.1, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn first_element_below_ground_has_contact() {
+        // chain of one element deployed downward from the origin
+        let mut scissor = straight();
+        scissor.solve(VariableFPolVec2::from(1.2, std::f64::consts::PI)).unwrap();
+        let contacts = scissor.find_collisions(0.1, Some(0.0)).unwrap();
+        assert_eq!(contacts.len(), 2);
+        for contact in contacts {
+            assert!(matches!(contact, ScissorContact::Ground{element_index: 0, distance, ..} if distance < -1.0));
+        }
+        // deployed upward, origin joints stand on the ground and are not contacts
+        let mut scissor = straight();
+        scissor.solve(VariableFPolVec2::from(1.2, 0.0)).unwrap();
+        assert!(scissor.find_collisions(0.1, Some(0.0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sweep_finds_the_first_contact_radius() {
+        let scissor = folding(6);
+        let tolerance = 1e-10;
+        let (radius, contacts) = scissor.find_first_collision(0.1, None, 0.0, SweepRange{start: 1.6, end: 0.9, steps: 70, tolerance}).unwrap().unwrap();
+        assert!(!contacts.is_empty());
+        let has_contact = |radius: f64| {
+            let mut scissor = folding(6);
+            scissor.solve(VariableFPolVec2::from(radius, 0.0)).unwrap();
+            !scissor.find_collisions(0.1, None).unwrap().is_empty()
+        };
+        // clear just before the contact radius
+        assert!(!has_contact(radius + 2.0 * tolerance));
+        // fine scan from the start agrees with the refined radius
+        let scanned = (0..7000).map(|i| 1.6 - 1e-4 * i as f64).find(|&radius| has_contact(radius)).unwrap();
+        assert!(scanned <= radius && radius - scanned < 1e-4, "{} != {}", radius, scanned);
+        assert!(scissor.find_first_collision(0.1, None, 0.0, SweepRange{start: 1.6, end: 1.4, steps: 20, tolerance}).unwrap().is_none());
+        assert!(scissor.find_first_collision(0.1, None, 0.0, SweepRange{start: 1.0, end: 1.6, steps: 20, tolerance}).is_err());
+    }
+}
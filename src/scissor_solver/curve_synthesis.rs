@@ -0,0 +1,110 @@
+use std::io::Error;
+
+use super::{Scissor, ScissorDimension, ScissorJointRole};
+use crate::mech_solver::optimize;
+use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+// CurveSynthesisResult is the result of fit_to_curve
+// residual : root mean square distance from the fitted joints to the target curve
+#[derive(Clone)]
+pub struct CurveSynthesisResult{
+    pub dimensions: Vec<ScissorDimension>,
+    pub residual: f64,
+}
+
+// choose c and d of every element so that the intermediate joints of the deployed scissor lie on the target curve
+// initial : dimensions to start from
+// input_radius, input_theta : input that deploys the scissor
+// curve : target polyline
+// roles : roles of the intermediate joints to fit, e.g. [AEnd] for the outer nodes or [Pivot] for the center line
+// a origin and b origin of the first element are given by the input, so they are not fitted
+// optimize_length : if true, a and b are also optimized
+pub fn fit_to_curve(initial: &[ScissorDimension], input_radius: f64, input_theta: f64, curve: &[(f64, f64)], roles: &[ScissorJointRole], optimize_length: bool, max_iter: usize) -> Result<CurveSynthesisResult, Error>{
+    if curve.len() < 2 {return Err(Error::other("target curve needs at least 2 points"));}
+    if roles.is_empty() {return Err(Error::other("no joint to fit"));}
+    let to_dimensions = |x: &[f64]| -> Vec<ScissorDimension> {
+        initial.iter().enumerate().map(|(i, dimension)| {
+            if optimize_length {
+                ScissorDimension{a: x[4 * i], b: x[4 * i + 1], c: x[4 * i + 2], d: x[4 * i + 3]}
+            } else {
+                ScissorDimension{a: dimension.a, b: dimension.b, c: x[2 * i], d: x[2 * i + 1]}
+            }
+        }).collect()
+    };
+    // mean square distance, dimensions that are invalid or cannot be assembled are penalized
+    let error = |x: &[f64]| -> f64 {
+        match intermediate_joints(&to_dimensions(x), input_radius, input_theta, roles) {
+            Ok(joints) if !joints.is_empty() => joints.iter().map(|&joint| distance_to_polyline(joint, curve).powi(2)).sum::<f64>() / joints.len() as f64,
+            _ => f64::MAX,
+        }
+    };
+    let x0: Vec<f64> = initial.iter().flat_map(|dimension| {
+        if optimize_length {vec![dimension.a, dimension.b, dimension.c, dimension.d]} else {vec![dimension.c, dimension.d]}
+    }).collect();
+    if error(&x0) == f64::MAX {return Err(Error::other("initial scissor cannot be assembled"));}
+    let step: Vec<f64> = x0.iter().map(|x| 0.05 * x).collect();
+    let (x, value) = optimize::nelder_mead(error, &x0, &step, max_iter, 1e-16);
+    Ok(CurveSynthesisResult{
+        dimensions: to_dimensions(&x),
+        residual: value.sqrt(),
+    })
+}
+
+// joints of the solved scissor whose role is in roles, except the origins of the first element
+fn intermediate_joints(dimensions: &[ScissorDimension], input_radius: f64, input_theta: f64, roles: &[ScissorJointRole]) -> Result<Vec<(f64, f64)>, Error>{
+    let mut scissor = Scissor::new(dimensions.to_vec()).map_err(|error| Error::other(error.to_string()))?;
+    scissor.solve(VariableFPolVec2::from(input_radius, input_theta))?;
+    Ok(scissor.get_joints()?.iter()
+        .filter(|joint| roles.contains(&joint.role))
+        .filter(|joint| !(joint.element_index == 0 && matches!(joint.role, ScissorJointRole::AOrigin | ScissorJointRole::BOrigin)))
+        .map(|joint| joint.position())
+        .collect())
+}
+
+fn distance_to_polyline(point: (f64, f64), polyline: &[(f64, f64)]) -> f64{
+    polyline.windows(2).map(|segment| {
+        let (p, q) = (segment[0], segment[1]);
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let len2 = dx * dx + dy * dy;
+        let t = if len2 == 0.0 {0.0} else {(((point.0 - p.0) * dx + (point.1 - p.1) * dy) / len2).clamp(0.0, 1.0)};
+        ((p.0 + t * dx - point.0).powi(2) + (p.1 + t * dy - point.1).powi(2)).sqrt()
+    }).fold(f64::MAX, f64::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // joints of a known scissor are used as the target, so the fit can reach zero residual
+    fn target(roles: &[ScissorJointRole]) -> Vec<(f64, f64)> {
+        let dimensions = [ScissorDimension{a: 2.0, b: 2.0, c: 1.2, d: 0.8}, ScissorDimension{a: 2.0, b: 2.0, c: 1.1, d: 0.9}];
+        intermediate_joints(&dimensions, 1.0, 0.0, roles).unwrap()
+    }
+
+    #[test]
+    fn fitted_joints_lie_on_the_curve() {
+        let roles = [ScissorJointRole::AEnd];
+        let curve = target(&roles);
+        let initial = vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 2];
+        let result = fit_to_curve(&initial, 1.0, 0.0, &curve, &roles, false, 5000).unwrap();
+        assert!(result.residual < 1e-6, "{}", result.residual);
+        let joints = intermediate_joints(&result.dimensions, 1.0, 0.0, &roles).unwrap();
+        assert_eq!(joints.len(), 2);
+        for joint in joints {
+            assert!(distance_to_polyline(joint, &curve) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn origins_of_the_first_element_are_not_fitted() {
+        assert_eq!(target(&[ScissorJointRole::AOrigin, ScissorJointRole::BOrigin]).len(), 2);
+    }
+
+    #[test]
+    fn invalid_target_is_error() {
+        let initial = vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 2];
+        assert!(fit_to_curve(&initial, 1.0, 0.0, &[(0.0, 0.0)], &[ScissorJointRole::AEnd], false, 10).is_err());
+        assert!(fit_to_curve(&initial, 1.0, 0.0, &[(0.0, 0.0), (1.0, 1.0)], &[], false, 10).is_err());
+        assert!(fit_to_curve(&initial, 5.0, 0.0, &[(0.0, 0.0), (1.0, 1.0)], &[ScissorJointRole::AEnd], false, 10).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use std::io::Error;
+
+use super::{Scissor, ScissorJointRole, JOINTS_PER_ELEMENT, actuator::{LinearActuator, ScissorJointRef}, collision::ScissorBar};
+use crate::mech_solver::linear_system;
+use crate::mech_solver::triangle_solver::variable_vector::{VariableF, VariableFPolVec2};
+
+// force in one bar
+// axial_inner : axial force between origin and pivot (tension is positive)
+// axial_outer : axial force between pivot and endpoint (tension is positive)
+// bending_moment : bending moment at the pivot (counterclockwise is positive)
+#[derive(Debug, Clone, Copy)]
+pub struct ScissorBarForce{
+    pub element_index: usize,
+    pub bar: ScissorBar,
+    pub axial_inner: f64,
+    pub axial_outer: f64,
+    pub bending_moment: f64,
+}
+
+// ScissorLoadState is the static equilibrium of the scissor at one input
+// actuator_force : axial force of the actuator (tension is positive)
+#[derive(Debug, Clone)]
+pub struct ScissorLoadState{
+    pub input_radius: f64,
+    pub actuator_force: f64,
+    pub bar_forces: Vec<ScissorBarForce>,
+}
+
+// force on a point of a bar, constant part and coefficient of each unknown
+#[derive(Clone, Default)]
+struct PointForce{
+    constant: (f64, f64),
+    unknowns: Vec<(usize, (f64, f64))>,
+}
+
+impl PointForce{
+    fn evaluate(&self, x: &[f64]) -> (f64, f64){
+        self.unknowns.iter().fold(self.constant, |sum, &(k, coefficient)| (sum.0 + coefficient.0 * x[k], sum.1 + coefficient.1 * x[k]))
+    }
+}
+
+// slot of the point in the bar
+const ORIGIN: usize = 0;
+const PIVOT: usize = 1;
+const END: usize = 2;
+
+// bar and point where force on the joint is applied
+// joints shared by two elements are applied to the bar of the element given by the reference
+fn bar_point(joint: ScissorJointRef) -> (usize, usize){
+    let (bar, point) = match joint.role{
+        ScissorJointRole::AOrigin => (0, ORIGIN),
+        ScissorJointRole::BOrigin => (1, ORIGIN),
+        ScissorJointRole::Pivot => (0, PIVOT),
+        ScissorJointRole::AEnd => (0, END),
+        ScissorJointRole::BEnd => (1, END),
+    };
+    (2 * joint.element_index + bar, point)
+}
+
+impl Scissor{
+    // static equilibrium of the solved scissor
+    // a origin of the first element is pinned, b origin of the first element slides along the input
+    // the actuator holds the scissor against the loads, loads are force vectors on joints
+    // every bar is a rigid body pinned at origin, pivot and endpoint
+    pub fn get_load_state(&self, actuator: &LinearActuator, loads: &[(ScissorJointRef, (f64, f64))]) -> Result<ScissorLoadState, Error>{
+        let (input_radius, input_theta) = if let (VariableF::Fixed(r), VariableF::Fixed(t)) = (self.input.radius, self.input.theta){
+            (r, t)
+        }else{
+            return Err(Error::other("input must be fixed"));
+        };
+        let joints = self.get_joints()?;
+        let element_count = self.elements.len();
+        for joint in [actuator.from, actuator.to].iter().chain(loads.iter().map(|(joint, _)| joint)){
+            if joint.element_index >= element_count {
+                return Err(Error::other(format!("element {} does not exist", joint.element_index)));
+            }
+        }
+        // position of origin, pivot and endpoint of every bar
+        let points: Vec<[(f64, f64); 3]> = joints.chunks(JOINTS_PER_ELEMENT).flat_map(|element_joints| {
+            let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| element_joints[i].position());
+            [[a_origin, pivot, a_end], [b_origin, pivot, b_end]]
+        }).collect();
+        let mut forces = vec![[PointForce::default(), PointForce::default(), PointForce::default()]; points.len()];
+        let mut unknown_count = 0;
+        // pin between two bars, force on first bar is unknown and second bar gets the reaction
+        let mut add_pin = |forces: &mut Vec<[PointForce; 3]>, first: (usize, usize), second: (usize, usize)|{
+            forces[first.0][first.1].unknowns.extend([(unknown_count, (1.0, 0.0)), (unknown_count + 1, (0.0, 1.0))]);
+            forces[second.0][second.1].unknowns.extend([(unknown_count, (-1.0, 0.0)), (unknown_count + 1, (0.0, -1.0))]);
+            unknown_count += 2;
+        };
+        for element_index in 0..element_count{
+            let (bar_a, bar_b) = (2 * element_index, 2 * element_index + 1);
+            add_pin(&mut forces, (bar_a, PIVOT), (bar_b, PIVOT));
+            // a end is b origin of the next element and b end is a origin of the next
+            if element_index + 1 < element_count{
+                add_pin(&mut forces, (bar_a, END), (bar_b + 2, ORIGIN));
+                add_pin(&mut forces, (bar_b, END), (bar_a + 2, ORIGIN));
+            }
+        }
+        // supports
+        forces[0][ORIGIN].unknowns.extend([(unknown_count, (1.0, 0.0)), (unknown_count + 1, (0.0, 1.0))]);
+        forces[1][ORIGIN].unknowns.push((unknown_count + 2, (-input_theta.sin(), input_theta.cos())));
+        unknown_count += 3;
+        // actuator
+        let from = bar_point(actuator.from);
+        let to = bar_point(actuator.to);
+        let (from_position, to_position) = (points[from.0][from.1], points[to.0][to.1]);
+        let length = ((to_position.0 - from_position.0).powi(2) + (to_position.1 - from_position.1).powi(2)).sqrt();
+        if length == 0.0 {return Err(Error::other("actuator has no length"));}
+        let direction = ((to_position.0 - from_position.0) / length, (to_position.1 - from_position.1) / length);
+        let actuator_unknown = unknown_count;
+        forces[from.0][from.1].unknowns.push((actuator_unknown, direction));
+        forces[to.0][to.1].unknowns.push((actuator_unknown, (-direction.0, -direction.1)));
+        unknown_count += 1;
+        for &(joint, load) in loads{
+            let (bar, point) = bar_point(joint);
+            let constant = &mut forces[bar][point].constant;
+            *constant = (constant.0 + load.0, constant.1 + load.1);
+        }
+        // force and moment equilibrium of every bar
+        let mut a = vec![vec![0.0; unknown_count]; 3 * points.len()];
+        let mut b = vec![0.0; 3 * points.len()];
+        for (bar, bar_forces) in forces.iter().enumerate(){
+            for (point, force) in bar_forces.iter().enumerate(){
+                let r = points[bar][point];
+                for &(k, f) in force.unknowns.iter(){
+                    a[3 * bar][k] += f.0;
+                    a[3 * bar + 1][k] += f.1;
+                    a[3 * bar + 2][k] += r.0 * f.1 - r.1 * f.0;
+                }
+                let f = force.constant;
+                b[3 * bar] -= f.0;
+                b[3 * bar + 1] -= f.1;
+                b[3 * bar + 2] -= r.0 * f.1 - r.1 * f.0;
+            }
+        }
+        let x = linear_system::solve_linear(a, b)
+            .ok_or(Error::other("scissor cannot be held by the actuator in this position"))?;
+        let unit = |from: (f64, f64), to: (f64, f64)| {
+            let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+            ((to.0 - from.0) / length, (to.1 - from.1) / length)
+        };
+        let bar_forces = forces.iter().enumerate().map(|(bar, bar_forces)| {
+            let [origin, pivot, end] = points[bar];
+            let origin_force = bar_forces[ORIGIN].evaluate(&x);
+            let end_force = bar_forces[END].evaluate(&x);
+            let inner = unit(origin, pivot);
+            let outer = unit(pivot, end);
+            ScissorBarForce{
+                element_index: bar / 2,
+                bar: if bar % 2 == 0 {ScissorBar::A} else {ScissorBar::B},
+                axial_inner: -(origin_force.0 * inner.0 + origin_force.1 * inner.1),
+                axial_outer: end_force.0 * outer.0 + end_force.1 * outer.1,
+                bending_moment: (origin.0 - pivot.0) * origin_force.1 - (origin.1 - pivot.1) * origin_force.0,
+            }
+        }).collect();
+        Ok(ScissorLoadState{
+            input_radius,
+            actuator_force: x[actuator_unknown],
+            bar_forces,
+        })
+    }
+    // load state for every input_radius from start_radius to end_radius
+    pub fn get_load_curve(&self, actuator: &LinearActuator, loads: &[(ScissorJointRef, (f64, f64))], input_theta: f64, start_radius: f64, end_radius: f64, steps: usize) -> Result<Vec<ScissorLoadState>, Error>{
+        (0..=steps).map(|i| {
+            let radius = start_radius + (end_radius - start_radius) * i as f64 / steps.max(1) as f64;
+            let mut scissor = self.clone();
+            scissor.solve(VariableFPolVec2::from(radius, input_theta))?;
+            scissor.get_load_state(actuator, loads)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scissor_solver::ScissorDimension;
+
+    fn solved(radius: f64) -> Scissor {
+        let mut scissor = Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.0, d: 1.0}; 2]).unwrap();
+        scissor.solve(VariableFPolVec2::from(radius, 0.0)).unwrap();
+        scissor
+    }
+
+    // actuator between the two origins of the first element, its length is input_radius
+    const ACTUATOR: LinearActuator = LinearActuator{
+        from: ScissorJointRef{element_index: 0, role: ScissorJointRole::AOrigin},
+        to: ScissorJointRef{element_index: 0, role: ScissorJointRole::BOrigin},
+    };
+
+    #[test]
+    fn actuator_force_matches_virtual_work() {
+        // work of the load equals work of the actuator for a small change of input_radius
+        let radius = 1.2;
+        let tip = ScissorJointRef{element_index: 1, role: ScissorJointRole::AEnd};
+        let load = (0.3, -1.0);
+        let state = solved(radius).get_load_state(&ACTUATOR, &[(tip, load)]).unwrap();
+        let step = 1e-6;
+        let tip_at = |radius: f64| Scissor::find_joint(&solved(radius).get_joints().unwrap(), tip).unwrap();
+        let (from, to) = (tip_at(radius - step), tip_at(radius + step));
+        let work = (load.0 * (to.0 - from.0) + load.1 * (to.1 - from.1)) / (2.0 * step);
+        assert!((state.actuator_force - work).abs() < 1e-6, "{} != {}", state.actuator_force, work);
+        assert_eq!(state.input_radius, radius);
+        assert_eq!(state.bar_forces.len(), 4);
+    }
+
+    #[test]
+    fn no_load_gives_no_force() {
+        let state = solved(1.2).get_load_state(&ACTUATOR, &[]).unwrap();
+        assert!(state.actuator_force.abs() < 1e-12);
+        for force in state.bar_forces.iter() {
+            assert!(force.axial_inner.abs() < 1e-12 && force.axial_outer.abs() < 1e-12 && force.bending_moment.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn unknown_element_is_error() {
+        let load = (ScissorJointRef{element_index: 2, role: ScissorJointRole::AEnd}, (0.0, -1.0));
+        assert!(solved(1.2).get_load_state(&ACTUATOR, &[load]).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use thiserror::Error;
+
+use super::{Scissor, ScissorDimension, ScissorElement, ScissorNewErr};
+use crate::mech_solver::triangle_solver::variable_vector::{VariableF, VariableFRecVec2};
+
+// ScissorUnit is one scissor element in a network
+// nodes : index of the nodes at [a origin, b origin, a end, b end]
+// unit can be solved from (a origin, b origin) or from (a end, b end)
+#[derive(Clone, Copy)]
+pub struct ScissorUnit{
+    pub dimension: ScissorDimension,
+    pub nodes: [usize; 4],
+}
+
+#[derive(Error, Debug)]
+pub enum NetworkErr{
+    #[error("Node {node} of unit {unit} does not exist.")]
+    NodeNotFound{unit: usize, node: usize},
+    #[error(transparent)]
+    InvalidDimension(#[from] ScissorNewErr),
+    #[error("Hint shortage. Node {node} cannot be solved from the inputs.")]
+    NotSolved{node: usize},
+    #[error("Network is locked. Unit {unit} puts node {node} {error} away from its position.")]
+    Inconsistent{unit: usize, node: usize, error: f64},
+    #[error("Unit {unit} cannot be assembled. {message}")]
+    CannotAssemble{unit: usize, message: String},
+}
+
+// ScissorNetwork is a graph of scissor units sharing end nodes (grid or branching scissor)
+#[derive(Clone)]
+pub struct ScissorNetwork{
+    node_count: usize,
+    units: Vec<ScissorUnit>,
+    // element to solve from (a origin, b origin) and one to solve from (a end, b end) for each unit
+    elements: Vec<[ScissorElement; 2]>,
+}
+
+impl ScissorNetwork{
+    pub fn new(node_count: usize, units: Vec<ScissorUnit>) -> Result<Self, NetworkErr>{
+        for (unit_index, unit) in units.iter().enumerate(){
+            if let Some(&node) = unit.nodes.iter().find(|&&node| node >= node_count){
+                return Err(NetworkErr::NodeNotFound{unit: unit_index, node});
+            }
+        }
+        // element index of ScissorNewErr is same as unit index
+        let forward = Scissor::new(units.iter().map(|unit| unit.dimension).collect())?.elements;
+        // reversed unit : a end becomes a origin and b end becomes b origin
+        let backward = Scissor::new(units.iter().map(|unit| ScissorDimension{
+            a: unit.dimension.a,
+            b: unit.dimension.b,
+            c: unit.dimension.a - unit.dimension.c,
+            d: unit.dimension.b - unit.dimension.d,
+        }).collect())?.elements;
+        Ok(ScissorNetwork{
+            node_count,
+            units,
+            elements: forward.into_iter().zip(backward).map(|(forward, backward)| [forward, backward]).collect(),
+        })
+    }
+    // mobility by Gruebler's equation, rigid body motion of the whole network is excluded
+    // each unit has 2 bars and 1 pivot, node shared by k bars has k - 1 pins
+    // networks with closed loops can be mobile even if this is 0 or less, use check_mobility
+    pub fn get_mobility(&self) -> isize{
+        let mut bars_at_node = vec![0isize; self.node_count];
+        for unit in self.units.iter(){
+            for &node in unit.nodes.iter(){
+                bars_at_node[node] += 1;
+            }
+        }
+        let bars = 2 * self.units.len() as isize;
+        let pins = self.units.len() as isize + bars_at_node.iter().filter(|&&count| count > 0).map(|count| count - 1).sum::<isize>();
+        3 * bars - 2 * pins - 3
+    }
+    // solve position of every node from fixed nodes
+    // unit is solved when both nodes of its origin or end are known, and it is repeated until all units are solved
+    // if a node is solved by two units to different positions farther than tolerance, the network is locked
+    pub fn solve(&self, fixed: &[(usize, (f64, f64))], tolerance: f64) -> Result<Vec<(f64, f64)>, NetworkErr>{
+        let mut positions: Vec<Option<(f64, f64)>> = vec![None; self.node_count];
+        for &(node, position) in fixed{
+            *positions.get_mut(node).ok_or(NetworkErr::NotSolved{node})? = Some(position);
+        }
+        let mut elements = self.elements.clone();
+        let mut solved = vec![false; self.units.len()];
+        loop {
+            let mut progress = false;
+            for (unit_index, unit) in self.units.iter().enumerate(){
+                if solved[unit_index] {continue;}
+                let [a_origin, b_origin, a_end, b_end] = unit.nodes;
+                let (direction, from, to) = match (positions[a_origin], positions[b_origin], positions[a_end], positions[b_end]){
+                    (Some(a), Some(b), _, _) => (0, (a, b), [a_end, b_end]),
+                    (_, _, Some(a), Some(b)) => (1, (a, b), [a_origin, b_origin]),
+                    _ => continue,
+                };
+                let element = &mut elements[unit_index][direction];
+                let input = VariableFRecVec2::from(from.1.0 - from.0.0, from.1.1 - from.0.1).to_pol();
+                element.solve(input).map_err(|error| NetworkErr::CannotAssemble{unit: unit_index, message: error.to_string()})?;
+                let (bar_a, bar_b) = match (element.a.to_rec(), element.b.to_rec()){
+                    (
+                        VariableFRecVec2{x: VariableF::Fixed(ax), y: VariableF::Fixed(ay)},
+                        VariableFRecVec2{x: VariableF::Fixed(bx), y: VariableF::Fixed(by)},
+                    ) => ((ax, ay), (bx, by)),
+                    _ => return Err(NetworkErr::CannotAssemble{unit: unit_index, message: "element is not fixed".to_string()}),
+                };
+                let solved_positions = [(from.0.0 + bar_a.0, from.0.1 + bar_a.1), (from.1.0 + bar_b.0, from.1.1 + bar_b.1)];
+                for (node, position) in to.into_iter().zip(solved_positions){
+                    match positions[node]{
+                        Some(known) => {
+                            let error = ((known.0 - position.0).powi(2) + (known.1 - position.1).powi(2)).sqrt();
+                            if error > tolerance {return Err(NetworkErr::Inconsistent{unit: unit_index, node, error});}
+                        },
+                        None => positions[node] = Some(position),
+                    }
+                }
+                solved[unit_index] = true;
+                progress = true;
+            }
+            if !progress {break;}
+        }
+        positions.iter().enumerate()
+            .map(|(node, position)| position.ok_or(NetworkErr::NotSolved{node}))
+            .collect()
+    }
+    // check that the network can be moved by the distance between input_nodes
+    // input_nodes[0] is fixed at the origin and input_nodes[1] slides on x axis from start_radius to end_radius
+    // every sample must be solved without locking
+    pub fn check_mobility(&self, input_nodes: [usize; 2], start_radius: f64, end_radius: f64, steps: usize, tolerance: f64) -> Result<(), NetworkErr>{
+        for i in 0..=steps{
+            let radius = start_radius + (end_radius - start_radius) * i as f64 / steps.max(1) as f64;
+            self.solve(&[(input_nodes[0], (0.0, 0.0)), (input_nodes[1], (radius, 0.0))], tolerance)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scissor_solver::ScissorJointRole;
+    use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+    const DIMENSION: ScissorDimension = ScissorDimension{a: 2.0, b: 2.0, c: 1.2, d: 0.8};
+
+    // linear chain of 3 units, a end of a unit is b origin of the next and b end is a origin of the next
+    fn chain() -> ScissorNetwork {
+        let units = [[0, 1, 2, 3], [3, 2, 4, 5], [5, 4, 6, 7]].map(|nodes| ScissorUnit{dimension: DIMENSION, nodes});
+        ScissorNetwork::new(8, units.to_vec()).unwrap()
+    }
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn chain_matches_scissor() {
+        let positions = chain().solve(&[(0, (0.0, 0.0)), (1, (1.0, 0.0))], 1e-9).unwrap();
+        let mut scissor = Scissor::new(vec![DIMENSION; 3]).unwrap();
+        scissor.solve(VariableFPolVec2::from(1.0, 0.0)).unwrap();
+        for joint in scissor.get_joints().unwrap() {
+            let node = match joint.role {
+                ScissorJointRole::AEnd => 2 * joint.element_index + 2,
+                ScissorJointRole::BEnd => 2 * joint.element_index + 3,
+                _ => continue,
+            };
+            assert_close(positions[node], joint.position());
+        }
+    }
+
+    #[test]
+    fn chain_is_solved_backward_from_the_ends() {
+        let forward = chain().solve(&[(0, (0.0, 0.0)), (1, (1.0, 0.0))], 1e-9).unwrap();
+        let backward = chain().solve(&[(6, forward[6]), (7, forward[7])], 1e-9).unwrap();
+        for (forward, backward) in forward.into_iter().zip(backward) {
+            assert_close(forward, backward);
+        }
+    }
+
+    #[test]
+    fn chain_is_mobile() {
+        let network = chain();
+        assert_eq!(network.get_mobility(), 1);
+        network.check_mobility([0, 1], 0.5, 1.5, 10, 1e-9).unwrap();
+    }
+
+    #[test]
+    fn closed_loop_with_different_units_is_locked() {
+        // two units share every node but have different pivots
+        let units = vec![
+            ScissorUnit{dimension: DIMENSION, nodes: [0, 1, 2, 3]},
+            ScissorUnit{dimension: ScissorDimension{c: 1.0, d: 1.0, ..DIMENSION}, nodes: [0, 1, 2, 3]},
+        ];
+        let network = ScissorNetwork::new(4, units).unwrap();
+        assert!(network.get_mobility() < 1);
+        assert!(matches!(network.solve(&[(0, (0.0, 0.0)), (1, (1.0, 0.0))], 1e-9), Err(NetworkErr::Inconsistent{unit: 1, ..})));
+    }
+
+    #[test]
+    fn invalid_network_is_error() {
+        let unit = ScissorUnit{dimension: DIMENSION, nodes: [0, 1, 2, 4]};
+        assert!(matches!(ScissorNetwork::new(4, vec![unit]), Err(NetworkErr::NodeNotFound{unit: 0, node: 4})));
+        // node 4 is not connected to any unit
+        let unit = ScissorUnit{dimension: DIMENSION, nodes: [0, 1, 2, 3]};
+        let network = ScissorNetwork::new(5, vec![unit]).unwrap();
+        assert!(matches!(network.solve(&[(0, (0.0, 0.0)), (1, (1.0, 0.0))], 1e-9), Err(NetworkErr::NotSolved{node: 4})));
+        assert!(matches!(network.solve(&[(0, (0.0, 0.0)), (1, (10.0, 0.0))], 1e-9), Err(NetworkErr::CannotAssemble{unit: 0, ..})));
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::Error;
+
+use super::{Scissor, ScissorDimension, ScissorJointRole};
+use crate::mech_solver::triangle_solver::variable_vector::VariableFPolVec2;
+
+// PolarScissor is a scissor that deploys along an arc
+// dimensions : dimension of each element, every element is a polar unit (a = b = c + d, c != d)
+// input_radius : input that deploys the scissor onto the arc (input theta is 0)
+// center : center of the arc when the scissor is solved with input_radius
+// max_error : max distance between joints and the arc when the scissor is solved
+#[derive(Clone)]
+pub struct PolarScissor{
+    pub dimensions: Vec<ScissorDimension>,
+    pub input_radius: f64,
+    pub center: (f64, f64),
+    pub max_error: f64,
+}
+
+// generate dimensions of a polar scissor
+// arc_radius : radius of the center line of the deployed scissor
+// span : angle of the arc covered by the scissor (rad)
+// num : number of elements
+// depth : distance between inner and outer joints, it is the input_radius
+// tolerance : if joints of the solved scissor are farther from the arc than this, error is returned
+pub fn polar_scissor_dimensions(arc_radius: f64, span: f64, num: usize, depth: f64, tolerance: f64) -> Result<PolarScissor, Error>{
+    if num == 0 {return Err(Error::other("polar scissor needs at least 1 element"));}
+    if !(depth > 0.0 && arc_radius > depth / 2.0) {return Err(Error::other("arc_radius must be larger than depth / 2"));}
+    let unit_angle = span / num as f64;
+    if !(unit_angle > 0.0 && unit_angle < std::f64::consts::PI) {return Err(Error::other("span / num must be in (0, PI)"));}
+    let inner = arc_radius - depth / 2.0;
+    let outer = arc_radius + depth / 2.0;
+    // bar a goes from inner joint to outer joint of next radial line, bar b goes from outer to inner
+    // they cross on the bisector of the two radial lines, so pivot is found by similar triangles
+    let length = (inner * inner + outer * outer - 2.0 * inner * outer * unit_angle.cos()).sqrt();
+    let c = length * inner / (inner + outer);
+    let d = length - c;
+    let dimensions = vec![ScissorDimension{a: length, b: length, c, d}; num];
+    let center = (-inner, 0.0);
+    let max_error = arc_error(&dimensions, depth, center, inner, outer)?;
+    if max_error > tolerance {
+        return Err(Error::other(format!("deployed scissor is off the arc by {}", max_error)));
+    }
+    Ok(PolarScissor{
+        dimensions,
+        input_radius: depth,
+        center,
+        max_error,
+    })
+}
+
+// solve the scissor and return max distance between the joints and the arc
+// a origin of every element must be on the inner circle and b origin on the outer one
+fn arc_error(dimensions: &[ScissorDimension], depth: f64, center: (f64, f64), inner: f64, outer: f64) -> Result<f64, Error>{
+    let mut scissor = Scissor::new(dimensions.to_vec()).map_err(|error| Error::other(error.to_string()))?;
+    scissor.solve(VariableFPolVec2::from(depth, 0.0))?;
+    let distance = |point: (f64, f64)| ((point.0 - center.0).powi(2) + (point.1 - center.1).powi(2)).sqrt();
+    // a origin and b end are on the inner circle, b origin and a end are on the outer one
+    Ok(scissor.get_joints()?.iter().map(|joint| match joint.role{
+        ScissorJointRole::AOrigin | ScissorJointRole::BEnd => (distance(joint.position()) - inner).abs(),
+        ScissorJointRole::BOrigin | ScissorJointRole::AEnd => (distance(joint.position()) - outer).abs(),
+        ScissorJointRole::Pivot => 0.0,
+    }).fold(0.0, f64::max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joints_are_on_the_arc_and_cover_the_span() {
+        let (arc_radius, span, num, depth) = (5.0, 1.2, 6, 0.8);
+        let polar = polar_scissor_dimensions(arc_radius, span, num, depth, 1e-9).unwrap();
+        assert_eq!(polar.dimensions.len(), num);
+        assert_eq!(polar.input_radius, depth);
+        let mut scissor = Scissor::new(polar.dimensions.clone()).unwrap();
+        scissor.solve(VariableFPolVec2::from(polar.input_radius, 0.0)).unwrap();
+        let center = polar.center;
+        let polar_of = |point: (f64, f64)| {
+            let (x, y) = (point.0 - center.0, point.1 - center.1);
+            ((x * x + y * y).sqrt(), y.atan2(x).abs())
+        };
+        for joint in scissor.get_joints().unwrap() {
+            let (radius, angle) = polar_of(joint.position());
+            let unit = angle / (span / num as f64);
+            match joint.role {
+                ScissorJointRole::AOrigin | ScissorJointRole::BEnd => assert!((radius - (arc_radius - depth / 2.0)).abs() < 1e-9),
+                ScissorJointRole::BOrigin | ScissorJointRole::AEnd => assert!((radius - (arc_radius + depth / 2.0)).abs() < 1e-9),
+                // pivot is on the bisector of the two radial lines of the element
+                ScissorJointRole::Pivot => assert!((unit - (joint.element_index as f64 + 0.5)).abs() < 1e-9, "{}", unit),
+            }
+        }
+        let (_, end_angle) = polar_of(scissor.get_endpoint().unwrap());
+        assert!((end_angle - span).abs() < 1e-9, "{} != {}", end_angle, span);
+    }
+
+    #[test]
+    fn invalid_arc_is_error() {
+        assert!(polar_scissor_dimensions(5.0, 1.2, 0, 0.8, 1e-9).is_err());
+        assert!(polar_scissor_dimensions(0.3, 1.2, 6, 0.8, 1e-9).is_err());
+        assert!(polar_scissor_dimensions(5.0, 4.0, 1, 0.8, 1e-9).is_err());
+        assert!(polar_scissor_dimensions(5.0, -1.2, 6, 0.8, 1e-9).is_err());
+    }
+}
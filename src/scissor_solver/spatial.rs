@@ -0,0 +1,225 @@
+use std::io::Error;
+
+use super::{Scissor, ScissorDimension, ScissorJointRole, ScissorNewErr, InvalidScissorDimension, ScissorDimensionRule, JOINT_ROLES};
+use crate::mech_solver::triangle_solver::variable_vector::{self, VariableF, VariableFPolVec2, VariableFRecVec2, VariableFRecVec3};
+
+// SpatialScissorDimension is ScissorDimension with twist
+// twist : rotation of the element plane around its input (line from a origin to b origin) from the plane of the previous element (rad)
+// twist of the first element rotates it from xy plane
+// pivot axes of the elements are not parallel if twist is not 0
+#[derive(Clone, Copy)]
+pub struct SpatialScissorDimension{
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub twist: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpatialScissorJoint{
+    pub element_index: usize,
+    pub role: ScissorJointRole,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+// SpatialScissor is a scissor whose elements are in different planes
+// each element is solved in its own plane, and the planes are connected by revolute joints along the inputs
+// input of an element is on the rotation axis, so it has same coordinates in both planes
+#[derive(Clone)]
+pub struct SpatialScissor{
+    scissor: Scissor,
+    twists: Vec<f64>,
+}
+
+// axes of the element plane, x and y are on the plane and z is the pivot axis
+type Frame = [VariableFRecVec3; 3];
+
+// rotate the frame around the axis by angle (Rodrigues' rotation formula)
+fn rotate_frame(frame: Frame, axis: VariableFRecVec3, angle: f64) -> Frame{
+    let (cos, sin) = (angle.cos(), angle.sin());
+    frame.map(|v| {
+        let along = match variable_vector::dot_product_3d(axis, v){
+            VariableF::Fixed(dot) => dot * (1.0 - cos),
+            VariableF::Unknown => f64::NAN,
+        };
+        v * cos + variable_vector::cross_product_3d(axis, v) * sin + axis * along
+    })
+}
+
+fn to_global(frame: &Frame, local: VariableFRecVec2) -> VariableFRecVec3{
+    match (local.x, local.y){
+        (VariableF::Fixed(x), VariableF::Fixed(y)) => frame[0] * x + frame[1] * y,
+        _ => VariableFRecVec3{x: VariableF::Unknown, y: VariableF::Unknown, z: VariableF::Unknown},
+    }
+}
+
+fn fixed(vec: VariableFRecVec3) -> Option<(f64, f64, f64)>{
+    match (vec.x, vec.y, vec.z){
+        (VariableF::Fixed(x), VariableF::Fixed(y), VariableF::Fixed(z)) => Some((x, y, z)),
+        _ => None,
+    }
+}
+
+impl SpatialScissor{
+    pub fn new(dimensions: Vec<SpatialScissorDimension>) -> Result<Self, ScissorNewErr>{
+        let twists = dimensions.iter().map(|dimension| dimension.twist).collect();
+        // NaN twist would pass through the rotation of the frame and make every following joint NaN
+        let twist_invalid: Vec<InvalidScissorDimension> = dimensions.iter().enumerate()
+            .filter(|(_, dimension)| !dimension.twist.is_finite())
+            .map(|(element_index, _)| InvalidScissorDimension{element_index, rule: ScissorDimensionRule::TwistNotFinite})
+            .collect();
+        let scissor = match Scissor::new(dimensions.iter().map(|dimension| ScissorDimension{a: dimension.a, b: dimension.b, c: dimension.c, d: dimension.d}).collect()){
+            Ok(_) if !twist_invalid.is_empty() => return Err(ScissorNewErr::InvalidDimension{invalid: twist_invalid}),
+            Ok(scissor) => scissor,
+            Err(ScissorNewErr::InvalidDimension{mut invalid}) => {
+                invalid.extend(twist_invalid);
+                invalid.sort_by_key(|invalid| invalid.element_index);
+                return Err(ScissorNewErr::InvalidDimension{invalid});
+            },
+            Err(error) => return Err(error),
+        };
+        Ok(SpatialScissor{scissor, twists})
+    }
+    // planar scissor on xy plane, get_joints gives same x and y as Scissor::get_joints
+    pub fn from_scissor(scissor: Scissor) -> Self{
+        let twists = vec![0.0; scissor.elements.len()];
+        SpatialScissor{scissor, twists}
+    }
+    // input is the vector from a origin to b origin of the first element on xy plane
+    pub fn solve(&mut self, input: VariableFPolVec2) -> Result<(), Error>{
+        self.scissor.solve(input)
+    }
+    // planes of the solved elements
+    fn get_frames(&self) -> Result<Vec<Frame>, Error>{
+        let mut frame = [VariableFRecVec3::from(1.0, 0.0, 0.0), VariableFRecVec3::from(0.0, 1.0, 0.0), VariableFRecVec3::from(0.0, 0.0, 1.0)];
+        let mut input = self.scissor.input;
+        let mut frames = Vec::with_capacity(self.twists.len());
+        for (index, (element, twist)) in self.scissor.elements.iter().zip(self.twists.iter()).enumerate(){
+            let axis = to_global(&frame, input.to_rec());
+            let len = match axis.get_len(){
+                VariableF::Fixed(len) if len > 0.0 => len,
+                _ => return Err(Error::other(format!("not fixed at element {}", index))),
+            };
+            frame = rotate_frame(frame, axis / len, *twist);
+            frames.push(frame);
+            input = element.a - element.b - input;
+        }
+        Ok(frames)
+    }
+    // pivot axis of every element
+    pub fn get_pivot_axes(&self) -> Result<Vec<(f64, f64, f64)>, Error>{
+        self.get_frames()?.iter()
+            .map(|frame| fixed(frame[2]).ok_or(Error::other("element is not solved")))
+            .collect()
+    }
+    // every joint of the solved scissor in the same order as Scissor::get_joints
+    pub fn get_joints(&self) -> Result<Vec<SpatialScissorJoint>, Error>{
+        let frames = self.get_frames()?;
+        let mut next_vec_origin = VariableFRecVec3::from(0.0, 0.0, 0.0);
+        let mut next_vec_input = VariableFRecVec3::from_rec2(self.scissor.input.to_rec());
+        let mut joints = Vec::with_capacity(frames.len() * JOINT_ROLES.len());
+        for (index, (element, frame)) in self.scissor.elements.iter().zip(frames.iter()).enumerate(){
+            let b_origin = next_vec_origin + next_vec_input;
+            let positions = [
+                next_vec_origin,
+                b_origin,
+                next_vec_origin + to_global(frame, element.c.to_rec()),
+                next_vec_origin + to_global(frame, element.a.to_rec()),
+                b_origin + to_global(frame, element.b.to_rec()),
+            ];
+            for (role, position) in JOINT_ROLES.iter().zip(positions){
+                let (x, y, z) = fixed(position).ok_or(Error::other(format!("not fixed at element {}", index)))?;
+                joints.push(SpatialScissorJoint{element_index: index, role: *role, x, y, z});
+            }
+            next_vec_origin = positions[4];
+            next_vec_input = positions[3] - positions[4];
+        }
+        Ok(joints)
+    }
+    pub fn get_endpoint(&self) -> Result<(f64, f64, f64), Error>{
+        let joints = self.get_joints()?;
+        let endpoint = joints[joints.len() - 1];
+        Ok((endpoint.x, endpoint.y, endpoint.z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dimensions(twist: f64) -> Vec<SpatialScissorDimension> {
+        (0..3).map(|i| SpatialScissorDimension{a: 2.0, b: 2.0, c: 1.2, d: 0.8, twist: if i == 0 {0.0} else {twist}}).collect()
+    }
+
+    fn planar() -> Scissor {
+        let mut scissor = Scissor::new(vec![ScissorDimension{a: 2.0, b: 2.0, c: 1.2, d: 0.8}; 3]).unwrap();
+        scissor.solve(VariableFPolVec2::from(1.0, 0.3)).unwrap();
+        scissor
+    }
+
+    fn assert_same_as_planar(joints: &[SpatialScissorJoint]) {
+        let planar = planar().get_joints().unwrap();
+        assert_eq!(joints.len(), planar.len());
+        for (joint, planar) in joints.iter().zip(planar.iter()) {
+            assert_eq!((joint.element_index, joint.role), (planar.element_index, planar.role));
+            assert_eq!((joint.x, joint.y, joint.z), (planar.x, planar.y, 0.0));
+        }
+    }
+
+    #[test]
+    fn zero_twist_matches_scissor_exactly() {
+        let mut spatial = SpatialScissor::new(dimensions(0.0)).unwrap();
+        spatial.solve(VariableFPolVec2::from(1.0, 0.3)).unwrap();
+        assert_same_as_planar(&spatial.get_joints().unwrap());
+        assert_same_as_planar(&SpatialScissor::from_scissor(planar()).get_joints().unwrap());
+        for axis in spatial.get_pivot_axes().unwrap() {
+            assert_eq!(axis, (0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn twisted_elements_keep_bar_lengths() {
+        let mut spatial = SpatialScissor::new(dimensions(0.5)).unwrap();
+        spatial.solve(VariableFPolVec2::from(1.0, 0.3)).unwrap();
+        let joints = spatial.get_joints().unwrap();
+        let distance = |p: &SpatialScissorJoint, q: &SpatialScissorJoint| ((p.x - q.x).powi(2) + (p.y - q.y).powi(2) + (p.z - q.z).powi(2)).sqrt();
+        for element in joints.chunks(JOINT_ROLES.len()) {
+            let [a_origin, b_origin, pivot, a_end, b_end] = [0, 1, 2, 3, 4].map(|i| &element[i]);
+            assert!((distance(a_origin, a_end) - 2.0).abs() < 1e-12);
+            assert!((distance(b_origin, b_end) - 2.0).abs() < 1e-12);
+            assert!((distance(a_origin, pivot) - 1.2).abs() < 1e-12);
+            assert!((distance(b_origin, pivot) - 0.8).abs() < 1e-12);
+        }
+        // twisted elements leave the xy plane and their pivot axes are not parallel
+        assert!(joints.iter().any(|joint| joint.z.abs() > 1e-3));
+        let axes = spatial.get_pivot_axes().unwrap();
+        assert!((axes[1].2 - 0.5f64.cos()).abs() < 1e-12, "{:?}", axes);
+        assert!((axes[2].2 - 1.0).abs() > 1e-3);
+    }
+
+    #[test]
+    fn non_finite_twist_is_invalid() {
+        let mut twisted = dimensions(0.5);
+        twisted[1].twist = f64::NAN;
+        twisted[2].twist = f64::INFINITY;
+        match SpatialScissor::new(twisted.clone()) {
+            Err(ScissorNewErr::InvalidDimension{invalid}) => {
+                assert_eq!(invalid.iter().map(|invalid| (invalid.element_index, invalid.rule)).collect::<Vec<_>>(),
+                    vec![(1, ScissorDimensionRule::TwistNotFinite), (2, ScissorDimensionRule::TwistNotFinite)]);
+            },
+            _ => panic!("non-finite twist must be rejected"),
+        }
+        // reported together with the other rules
+        twisted[2].c = 3.0;
+        match SpatialScissor::new(twisted) {
+            Err(ScissorNewErr::InvalidDimension{invalid}) => {
+                assert_eq!(invalid.iter().map(|invalid| (invalid.element_index, invalid.rule)).collect::<Vec<_>>(),
+                    vec![(1, ScissorDimensionRule::TwistNotFinite), (2, ScissorDimensionRule::ANotLongerThanC), (2, ScissorDimensionRule::TwistNotFinite)]);
+            },
+            _ => panic!("invalid dimension must be rejected"),
+        }
+    }
+}
@@ -1,57 +1,145 @@
-use std::{vec, option::Iter, rc::Rc, cell::RefCell, ops::Index, pin::Pin};
-use cgmath::{Vector2, Matrix2, Rad, Deg, Angle, Basis2, Transform, Point2, Matrix3, Rotation};
+use std::f64::consts::PI;
+
+use cgmath::{Vector2, Point2, Rad, Basis2, Rotation, Rotation2, InnerSpace, EuclideanSpace};
 use thiserror::Error;
 
+pub mod collision;
+
+use crate::ffi::{FfiError, LinkageSimError};
+use crate::mech_solver::triangle_solver::{Triangle, variable_vector::{self, VariableF, VariableFPolVec2, VariableFRecVec2}};
+
+// Mechanism is a planar mechanism of rigid linkages connected by pin joints
+// linkages and joints are referred by index, joint connects (linkage index, joint index in the linkage) pairs
+// mechanism is solved from fixed joints (ground) and inputs (rotation of a linkage around a known joint)
+// joints are solved one by one : a linkage with two known joints is placed, and a joint on two linkages
+// that have one known joint each is solved as a dyad (intersection of two circles)
+#[derive(Clone)]
 pub struct Mechanism {
     joints: Vec<PinJoint>,
-    linkages: Vec<Rc<RefCell<Linkage>>>,
+    linkages: Vec<Linkage>,
+    hints: Vec<SolveHint>,
+    // result of the last successful solve
+    solved: Option<MechanismState>,
 }
 
+// input rotates the linkage around the joint, the joint must be fixed or solved from the other inputs
+// angle : rotation of the linkage from its local frame (rad)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
 pub struct MechInput {
-    joint_index: usize,
-    linkage_index: usize,
-    linkage_joint_index: usize,
-    rotation: Basis2<f64>,
+    pub joint_index: usize,
+    pub linkage_index: usize,
+    pub angle: f64,
 }
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum SolveErr{
     #[error("No anker founded. To solve Mechanism, You should fix 1 more joint.")]
     NoAnker,
     #[error("Hint shortage. To solve Mechanism, You should add more hint about joint at {at}.")]
     SolveHintshortage{at: usize},
+    #[error("Hint shortage. To solve Mechanism, You should add more hint about linkage {linkage}.")]
+    LinkageHintshortage{linkage: usize},
     #[error("Too much constraint on joint at {at}.")]
     TooMuchConstraint{at: usize},
+    #[error("Joint at {at} cannot be assembled.")]
+    CannotAssemble{at: usize},
+    #[error("Joint {joint} refers to joint {index} of linkage {linkage} that does not exist.")]
+    InvalidConnection{joint: usize, linkage: usize, index: usize},
+    #[error("Input {at} refers to a joint or linkage that does not exist or are not connected.")]
+    InvalidInput{at: usize},
+    #[error("Joint {joint} does not exist.")]
+    InvalidJoint{joint: usize},
+    #[error("Mechanism is not solved.")]
+    NotSolved,
 }
 
-#[derive(Clone)]
-struct PinJoint {
-    linkages: Vec<Rc<RefCell<Linkage>>>,
-    joint_indexs: Vec<usize>,
+#[derive(Debug, Clone)]
+pub struct PinJoint {
+    // (linkage index, joint index in the linkage)
+    connections: Vec<(usize, usize)>,
     tranceform: JointTranceform,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy)]
 enum JointTranceform {
     FixedTo(Point2<f64>),
-    TwoSolution([Point2<f64>; 2]),
     Floated,
 }
 
 /// it will solve to point that A x B is active.
 /// invert is select negative one.
 /// (A is vector [joint_index_from] to [joint_index_to\[0\]] and B is to [joint_index_to\[1\]])
+/// hint is used for the dyad whose three joints (solved joint and two known joints) are these joints
+/// without hint, the solved joint is placed on the left of the line between the two known joints
+#[derive(Debug, Clone, Copy)]
 pub struct SolveHint {
-    joint_index_from: usize,
-    joint_index_to: [usize; 2],
-    invert: bool,
+    pub joint_index_from: usize,
+    pub joint_index_to: [usize; 2],
+    pub invert: bool,
 }
 
+// Linkage is a rigid body, joints and lines are in its local frame
+// lines are the bars of the linkage, they are used for drawing and collision
+#[derive(Debug, Clone)]
 pub struct Linkage {
     joints: Vec<Vector2<f64>>,
     lines: Vec<[Vector2<f64>; 2]>,
 }
 
+// MechanismSolveReport is the result of Mechanism::solve_with_report
+// joints : position of every joint
+// transmission_angles : (joint index, transmission angle) of every dyad (rad), see Mechanism::get_transmission_angles
+// mechanical_advantage : output torque / input torque (ratio of input angle change to output linkage angle change)
+// poor_transmission : joint index of dyads whose transmission angle is out of [threshold, PI - threshold]
+#[derive(Debug, Clone)]
+pub struct MechanismSolveReport {
+    pub joints: Vec<(f64, f64)>,
+    pub transmission_angles: Vec<(usize, f64)>,
+    pub mechanical_advantage: f64,
+    pub poor_transmission: Vec<usize>,
+}
+
+// dyad solved joint from known joints, known[i] is on linkages[i]
+#[derive(Debug, Clone, Copy)]
+struct Dyad {
+    joint: usize,
+    known: [usize; 2],
+}
+
+#[derive(Debug, Clone)]
+struct MechanismState {
+    inputs: Vec<MechInput>,
+    positions: Vec<Point2<f64>>,
+    // rotation of each linkage from its local frame
+    angles: Vec<f64>,
+    // translation of each linkage, global = rotate(local, angle) + offset
+    offsets: Vec<Vector2<f64>>,
+    dyads: Vec<Dyad>,
+}
+
+// relative tolerance of the lengths checked while solving
+const TOLERANCE: f64 = 1e-9;
+
+fn rotate(vector: Vector2<f64>, angle: f64) -> Vector2<f64> {
+    Basis2::from_angle(Rad(angle)).rotate_vector(vector)
+}
+
+fn cross(a: Vector2<f64>, b: Vector2<f64>) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn to_variable(vector: Vector2<f64>) -> VariableFRecVec2 {
+    VariableFRecVec2::from(vector.x, vector.y)
+}
+
+fn fixed_point(vec: VariableFPolVec2) -> Option<Vector2<f64>> {
+    match vec.to_rec(){
+        VariableFRecVec2{x: VariableF::Fixed(x), y: VariableF::Fixed(y)} if x.is_finite() && y.is_finite() => Some(Vector2::new(x, y)),
+        _ => None,
+    }
+}
+
 impl Linkage {
     pub fn new() -> Self {
         Linkage{
@@ -73,30 +161,27 @@ impl Linkage {
     pub fn get_vector_from_origin(&self, index_to: usize) -> Vector2<f64> {
         self.joints[index_to]
     }
+    pub fn get_joint_count(&self) -> usize {
+        self.joints.len()
+    }
+}
+
+impl Default for Linkage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PinJoint{
     pub fn new() -> Self {
         PinJoint {
-            linkages: Vec::new(),
-            joint_indexs: Vec::new(),
+            connections: Vec::new(),
             tranceform: JointTranceform::Floated,
         }
     }
-    fn check_index_len(linkage: &Linkage, index: usize) -> Result<(),()> {
-        if linkage.joints.len() > index {
-            Result::Ok(())
-        } else {
-            Result::Err(())
-        }
-    }
-    pub fn from_linkage<const NUM: usize>(linkages: [Rc<RefCell<Linkage>>; NUM], indexs: [usize; NUM]) -> Self {
-        for i in 0..NUM {
-            Self::check_index_len(&*linkages[i].borrow(), indexs[i]).expect("index is bigger than Vector lengthof linkage!");
-        }
+    pub fn from_linkage<const NUM: usize>(linkages: [usize; NUM], indexs: [usize; NUM]) -> Self {
         PinJoint {
-            linkages: Vec::from(linkages),
-            joint_indexs: Vec::from(indexs),
+            connections: linkages.into_iter().zip(indexs).collect(),
             tranceform: JointTranceform::Floated,
         }
     }
@@ -104,25 +189,21 @@ impl PinJoint{
         self.tranceform = JointTranceform::FixedTo(point);
         self
     }
-    pub fn add_connection(mut self, linkage: Rc<RefCell<Linkage>>, index: usize) -> Self {
-        Self::check_index_len(&*linkage.borrow(), index).expect("index is bigger than Vector lengthof linkage!");
-        self.linkages.push(linkage);
-        self.joint_indexs.push(index);
+    pub fn add_connection(mut self, linkage: usize, index: usize) -> Self {
+        self.connections.push((linkage, index));
         self
     }
-    //search joint that have connection to same linkage
-    //return joint index vector
-    pub fn search_same_linkage(joints: Vec<Self>, linkage: &Rc<RefCell<Linkage>>) -> Vec<(usize, usize)> {
-        let mut ret = Vec::new();
-        for (i, joint) in joints.iter().enumerate() {
-            for (j, joint_linkage) in joint.linkages.iter().enumerate() {
-                if Rc::ptr_eq(&linkage, joint_linkage) {
-                    ret.push((i, joint.joint_indexs[j]));
-                    break;
-                }
-            }
+    fn get_fixed(&self) -> Option<Point2<f64>> {
+        match self.tranceform {
+            JointTranceform::FixedTo(point) => Some(point),
+            JointTranceform::Floated => None,
         }
-        ret
+    }
+}
+
+impl Default for PinJoint {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -131,104 +212,663 @@ impl Mechanism {
         Mechanism {
             joints: Vec::new(),
             linkages: Vec::new(),
+            hints: Vec::new(),
+            solved: None,
         }
     }
-    pub fn from_linkages<const NUM: usize>(linkages: [Linkage; NUM],) -> Self {
+    pub fn from_linkages(linkages: Vec<Linkage>) -> Self {
         Mechanism {
-            joints: Vec::new(),
-            linkages: Vec::from(linkages.map(|linkage|Rc::new(RefCell::new(linkage)))),
+            linkages,
+            ..Self::new()
         }
     }
-    pub fn set_angle_input(mut self, linkage_index: usize, angle: f64) -> Self{
-        self
+    // return index of the linkage
+    pub fn add_linkage(&mut self, linkage: Linkage) -> usize {
+        self.linkages.push(linkage);
+        self.solved = None;
+        self.linkages.len() - 1
     }
-    pub fn solve(&self, inputs: Vec<MechInput>) -> Result<Self,SolveErr> {
-        // count fixed joint and return error if there is no anker 
-        if {
-            let mut fixed_joint = 0;
-            for joint in &self.joints {
-                match joint.tranceform {
-                    JointTranceform::FixedTo(_) => {fixed_joint += 1},
-                    _ => {},
-                }
+    // return index of the joint, every connection must point an existing joint of a linkage
+    pub fn add_joint(&mut self, joint: PinJoint) -> Result<usize, SolveErr> {
+        let joint_index = self.joints.len();
+        for &(linkage, index) in joint.connections.iter() {
+            if self.linkages.get(linkage).is_none_or(|linkage| index >= linkage.joints.len()) {
+                return Err(SolveErr::InvalidConnection{joint: joint_index, linkage, index});
             }
-            fixed_joint
-        } == 0 {return Err(SolveErr::NoAnker)};
-        let mut joints = self.joints.clone();
-        let mut input_solved = vec![false; inputs.len()];
-        let mut no_solution_count = 0;
-        while {
-            let mut solved_joint = 0;
-            for joint in joints {
-                match joint.tranceform {
-                    JointTranceform::Floated => {solved_joint += 1},
-                    _ => {},
-                }
+        }
+        self.joints.push(joint);
+        self.solved = None;
+        Ok(joint_index)
+    }
+    // fix the joint to the ground at point
+    pub fn fix_joint(&mut self, joint: usize, point: Point2<f64>) -> Result<(), SolveErr> {
+        self.joints.get_mut(joint).ok_or(SolveErr::InvalidJoint{joint})?.tranceform = JointTranceform::FixedTo(point);
+        self.solved = None;
+        Ok(())
+    }
+    // solved state is cleared because the hint can change the branch
+    pub fn add_hint(&mut self, hint: SolveHint) {
+        self.hints.push(hint);
+        self.solved = None;
+    }
+    pub fn get_joint_count(&self) -> usize {
+        self.joints.len()
+    }
+    pub fn get_linkage_count(&self) -> usize {
+        self.linkages.len()
+    }
+    // solve the mechanism at the inputs, on error the mechanism keeps the previous solution
+    pub fn solve(&mut self, inputs: &[MechInput]) -> Result<(), SolveErr> {
+        let state = self.solve_state(inputs)?;
+        self.solved = Some(state);
+        Ok(())
+    }
+    // (joint index, local point) of every joint on each linkage
+    fn get_members(&self) -> Vec<Vec<(usize, Vector2<f64>)>> {
+        let mut members = vec![Vec::new(); self.linkages.len()];
+        for (joint_index, joint) in self.joints.iter().enumerate() {
+            for &(linkage, index) in joint.connections.iter() {
+                members[linkage].push((joint_index, self.linkages[linkage].joints[index]));
             }
-            solved_joint
-        } != joints.len() {
+        }
+        members
+    }
+    fn solve_state(&self, inputs: &[MechInput]) -> Result<MechanismState, SolveErr> {
+        // count fixed joint and return error if there is no anker
+        if self.joints.iter().all(|joint| joint.get_fixed().is_none()) {return Err(SolveErr::NoAnker);}
+        let members = self.get_members();
+        // local point of the input joint on the input linkage
+        let input_locals = inputs.iter().enumerate().map(|(at, input)| {
+            members.get(input.linkage_index)
+                .and_then(|members| members.iter().find(|(joint, _)| *joint == input.joint_index))
+                .filter(|_| input.angle.is_finite())
+                .map(|&(_, local)| local)
+                .ok_or(SolveErr::InvalidInput{at})
+        }).collect::<Result<Vec<_>, _>>()?;
+        let mut positions: Vec<Option<Point2<f64>>> = self.joints.iter().map(|joint| joint.get_fixed()).collect();
+        let mut poses: Vec<Option<(f64, Vector2<f64>)>> = vec![None; self.linkages.len()];
+        let mut input_solved = vec![false; inputs.len()];
+        let mut dyads = Vec::new();
+        loop {
+            let mut progress = false;
             //solve input
             for (i, input) in inputs.iter().enumerate() {
-                if input_solved[i] == false {
-                    match joints[input.joint_index].tranceform {
-                        JointTranceform::FixedTo(joint_cord) => {
-                            PinJoint::search_same_linkage(joints, &self.linkages[input.linkage_index]).iter().for_each(|(joint_index, linkage_joint_index)|{
-                                let linkage = self.linkages[input.linkage_index].borrow();
-                                let joint = &mut joints[*joint_index];
-                                match joint.tranceform {
-                                    JointTranceform::Floated => {
-                                        joint.tranceform = JointTranceform::FixedTo(input.rotation.rotate_vector(linkage.joints[*linkage_joint_index] - linkage.joints[input.linkage_joint_index]).into() + joint_cord);
-                                    },
-                                    _ => {},
-                                }
-                            });
-                            input_solved[i] = true;
-                        },
-                        _ => {},
-                    }
+                if input_solved[i] {continue;}
+                if let Some(position) = positions[input.joint_index] {
+                    if poses[input.linkage_index].is_some() {return Err(SolveErr::TooMuchConstraint{at: input.joint_index});}
+                    let offset = position.to_vec() - rotate(input_locals[i], input.angle);
+                    poses[input.linkage_index] = Some((input.angle, offset));
+                    Self::place_members(&members[input.linkage_index], (input.angle, offset), &mut positions)?;
+                    input_solved[i] = true;
+                    progress = true;
                 }
             }
-            //solve linkage
-            for (i, joint) in joints.iter_mut().enumerate() {
-                match joint.tranceform {
-                    JointTranceform::Floated => {
-                        match {
-                            // check joint could be solved
-                            let mut fixed_joint_count = 0;
-                            let mut fixed_joint_index = [0; 2];
-                            let mut fixed_linkage_index = [0; 2];
-                            let mut fixed_linkage_joint_index = [0; 2];
-                            joint.linkages.iter().enumerate().for_each(|i, linkage| {
-                                PinJoint::search_same_linkage(joints, linkage).iter().for_each(|(joint_index, linkage_joint_index)|{
-                                    if let JointTranceform::FixedTo(_) = joints[*joint_index].tranceform {
-                                        if let 3 = fixed_joint_count {
-                                            return;
-                                        }
-                                        fixed_joint_index[fixed_joint_count] = *joint_index;
-                                        fixed_linkage_index[fixed_joint_count] = i;
-                                        fixed_linkage_joint_index[fixed_joint_count] = linkage_joint_index;
-                                        fixed_joint_count += 1;
-                                    }
-                                });
-                            });
-                            (fixed_joint_count, fixed_joint_index, fixed_linkage_index, fixed_linkage_joint_index)
-                        } {
-                            //if 2 degree constraint is applied, solve joint
-                            (2, fixed_joint_index, fixed_linkage_index, fixed_linkage_joint_index) => {
-                                let fixed_linkage = [self.linkages[fixed_linkage_index[0]].borrow(), self.linkages[fixed_linkage_index[1]].borrow()];
-                                let fixed_joint = [joints[fixed_joint_index[0]], joints[fixed_joint_index[1]]];
-                                let fixed_linkage_joint = [fixed_linkage[0].joints[fixed_linkage_joint_index[0]], fixed_linkage[1].joints[fixed_linkage_joint_index[1]]];
-                                
-                            },
-                            //if over 3 degree constraint is applied, return error
-                            (count,_,_,_) if count >= 3 => {return Err(SolveErr::TooMuchConstraint { at: i })},
-                            _ => {},
-                        }
-                    },
-                    _ => {},
+            //solve linkage that has two known joints
+            for (linkage, linkage_members) in members.iter().enumerate() {
+                if poses[linkage].is_some() {continue;}
+                let known: Vec<(Vector2<f64>, Point2<f64>, usize)> = linkage_members.iter()
+                    .filter_map(|&(joint, local)| positions[joint].map(|position| (local, position, joint)))
+                    .collect();
+                let Some(&(local_from, global_from, _)) = known.first() else {continue;};
+                let Some(&(local_to, global_to, joint_to)) = known.iter().skip(1).find(|(local, _, _)| *local != local_from) else {continue;};
+                let (local, global) = (local_to - local_from, global_to - global_from);
+                if (local.magnitude() - global.magnitude()).abs() > TOLERANCE * (1.0 + local.magnitude()) {
+                    return Err(SolveErr::TooMuchConstraint{at: joint_to});
                 }
+                let angle = global.y.atan2(global.x) - local.y.atan2(local.x);
+                let pose = (angle, global_from.to_vec() - rotate(local_from, angle));
+                poses[linkage] = Some(pose);
+                Self::place_members(linkage_members, pose, &mut positions)?;
+                progress = true;
+            }
+            //solve dyad
+            for joint in 0..self.joints.len() {
+                if positions[joint].is_some() {continue;}
+                if let Some((dyad, position)) = self.solve_dyad(joint, &members, &positions)? {
+                    positions[joint] = Some(position);
+                    dyads.push(dyad);
+                    progress = true;
+                }
+            }
+            if !progress {break;}
+        }
+        if let Some(at) = positions.iter().position(|position| position.is_none()) {
+            return Err(SolveErr::SolveHintshortage{at});
+        }
+        if let Some(linkage) = poses.iter().position(|pose| pose.is_none()) {
+            return Err(SolveErr::LinkageHintshortage{linkage});
+        }
+        let (angles, offsets) = poses.into_iter().flatten().unzip();
+        Ok(MechanismState {
+            inputs: inputs.to_vec(),
+            positions: positions.into_iter().flatten().collect(),
+            angles,
+            offsets,
+            dyads,
+        })
+    }
+    // set position of every joint on the placed linkage
+    fn place_members(linkage_members: &[(usize, Vector2<f64>)], pose: (f64, Vector2<f64>), positions: &mut [Option<Point2<f64>>]) -> Result<(), SolveErr> {
+        for &(joint, local) in linkage_members {
+            let position = Point2::from_vec(rotate(local, pose.0) + pose.1);
+            match positions[joint] {
+                Some(known) if (known - position).magnitude() > TOLERANCE * (1.0 + local.magnitude()) => {
+                    return Err(SolveErr::TooMuchConstraint{at: joint});
+                },
+                Some(_) => {},
+                None => positions[joint] = Some(position),
+            }
+        }
+        Ok(())
+    }
+    // joint on two unplaced linkages that have one known joint each is on the intersection of two circles
+    fn solve_dyad(&self, joint: usize, members: &[Vec<(usize, Vector2<f64>)>], positions: &[Option<Point2<f64>>]) -> Result<Option<(Dyad, Point2<f64>)>, SolveErr> {
+        // (known joint, its position, distance to the joint) on each linkage of the joint
+        let arms: Vec<(usize, Point2<f64>, f64)> = self.joints[joint].connections.iter().filter_map(|&(linkage, index)| {
+            let local = self.linkages[linkage].joints[index];
+            members[linkage].iter()
+                .find_map(|&(other, other_local)| positions[other].filter(|_| other != joint).map(|position| (other, position, (other_local - local).magnitude())))
+        }).collect();
+        let Some(&(known_a, position_a, radius_a)) = arms.first() else {return Ok(None);};
+        let Some(&(known_b, position_b, radius_b)) = arms.iter().skip(1).find(|(known, _, _)| *known != known_a) else {return Ok(None);};
+        // a : known_a to joint, b : joint to known_b, c : known_b to known_a
+        let triangle = Triangle::new(
+            VariableFPolVec2::from_len(radius_a),
+            VariableFPolVec2::from_len(radius_b),
+            to_variable(position_a - position_b).to_pol(),
+        );
+        let candidates: Vec<Point2<f64>> = triangle.solve_branches()
+            .map_err(|_| SolveErr::CannotAssemble{at: joint})?
+            .into_iter()
+            .filter_map(|solution| fixed_point(solution.a).map(|a| position_a + a))
+            .collect();
+        if candidates.is_empty() {return Err(SolveErr::CannotAssemble{at: joint});}
+        let hint = self.hints.iter().find(|hint| {
+            let mut hint_joints = [hint.joint_index_from, hint.joint_index_to[0], hint.joint_index_to[1]];
+            let mut dyad_joints = [joint, known_a, known_b];
+            hint_joints.sort();
+            dyad_joints.sort();
+            hint_joints == dyad_joints
+        });
+        let side = |candidate: Point2<f64>| {
+            let at = |index: usize| if index == joint {candidate} else {positions[index].unwrap_or(candidate)};
+            match hint {
+                Some(hint) => {
+                    let from = at(hint.joint_index_from);
+                    let side = cross(at(hint.joint_index_to[0]) - from, at(hint.joint_index_to[1]) - from);
+                    if hint.invert {-side} else {side}
+                },
+                None => cross(position_b - position_a, candidate - position_a),
+            }
+        };
+        let position = candidates.iter().copied()
+            .max_by(|&p, &q| side(p).partial_cmp(&side(q)).unwrap_or(std::cmp::Ordering::Equal))
+            .ok_or(SolveErr::CannotAssemble{at: joint})?;
+        Ok(Some((Dyad{joint, known: [known_a, known_b]}, position)))
+    }
+    fn get_state(&self) -> Result<&MechanismState, SolveErr> {
+        self.solved.as_ref().ok_or(SolveErr::NotSolved)
+    }
+    // position of every joint of the solved mechanism
+    pub fn get_joints(&self) -> Result<Vec<(f64, f64)>, SolveErr> {
+        Ok(self.get_state()?.positions.iter().map(|position| (position.x, position.y)).collect())
+    }
+    // rotation of the linkage from its local frame in the solved mechanism (rad)
+    pub fn get_linkage_angle(&self, linkage: usize) -> Result<f64, SolveErr> {
+        self.get_state()?.angles.get(linkage).copied().ok_or(SolveErr::LinkageHintshortage{linkage})
+    }
+    // transmission angle of every dyad, (solved joint index, angle) in the order the dyads are solved
+    // transmission angle is the angle at the solved joint between the two linkages of the dyad (0 ~ PI)
+    // for a four-bar it is the angle between the coupler and the rocker
+    pub fn get_transmission_angles(&self) -> Result<Vec<(usize, f64)>, SolveErr> {
+        let state = self.get_state()?;
+        state.dyads.iter().map(|dyad| {
+            let joint = state.positions[dyad.joint];
+            let [arm_a, arm_b] = dyad.known.map(|known| to_variable(state.positions[known] - joint));
+            let lengths = arm_a.to_pol().radius * arm_b.to_pol().radius;
+            match (variable_vector::dot_product(arm_a, arm_b), lengths) {
+                (VariableF::Fixed(dot), VariableF::Fixed(lengths)) if lengths > 0.0 => Ok((dyad.joint, (dot / lengths).clamp(-1.0, 1.0).acos())),
+                _ => Err(SolveErr::CannotAssemble{at: dyad.joint}),
             }
+        }).collect()
+    }
+    // mechanical advantage between inputs[input] of the last solve and the output linkage
+    // calculated by central difference of the input angle, one sided difference is used if one side cannot be assembled
+    pub fn get_mechanical_advantage(&self, input: usize, output_linkage: usize) -> Result<f64, SolveErr> {
+        let state = self.get_state()?;
+        let base = *state.inputs.get(input).ok_or(SolveErr::InvalidInput{at: input})?;
+        if output_linkage >= self.linkages.len() {return Err(SolveErr::LinkageHintshortage{linkage: output_linkage});}
+        let step = 1e-6;
+        let output_at = |angle: f64| -> Option<f64> {
+            let mut inputs = state.inputs.clone();
+            inputs[input].angle = angle;
+            self.solve_state(&inputs).ok().map(|solved| solved.angles[output_linkage])
+        };
+        let (from, to, input_diff) = match (output_at(base.angle - step), output_at(base.angle + step)) {
+            (Some(from), Some(to)) => (from, to, 2.0 * step),
+            (None, Some(to)) => (state.angles[output_linkage], to, step),
+            (Some(from), None) => (from, state.angles[output_linkage], step),
+            (None, None) => return Err(SolveErr::CannotAssemble{at: base.joint_index}),
+        };
+        // difference of the angles wrapped into (-PI, PI]
+        let output_diff = PI - (PI - (to - from)).rem_euclid(2.0 * PI);
+        Ok((input_diff / output_diff).abs())
+    }
+    // solve and report transmission angles and mechanical advantage of inputs[0] to the output linkage
+    // min_transmission_angle is the threshold in radian (e.g. 40deg = 0.698)
+    pub fn solve_with_report(&mut self, inputs: &[MechInput], output_linkage: usize, min_transmission_angle: f64) -> Result<MechanismSolveReport, SolveErr> {
+        self.solve(inputs)?;
+        let transmission_angles = self.get_transmission_angles()?;
+        let poor_transmission = transmission_angles.iter()
+            .filter(|(_, angle)| *angle < min_transmission_angle || *angle > PI - min_transmission_angle)
+            .map(|(joint, _)| *joint)
+            .collect();
+        Ok(MechanismSolveReport {
+            joints: self.get_joints()?,
+            transmission_angles,
+            mechanical_advantage: self.get_mechanical_advantage(0, output_linkage)?,
+            poor_transmission,
+        })
+    }
+}
+
+impl Default for Mechanism {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// opaque handle API of Mechanism, in the same manner as scissor_new
+// handle is created by mechanism_new and must be freed by mechanism_free
+// ground is set by mechanism_fix_joint and inputs are given to mechanism_solve and mechanism_solve_sweep
+// pointers passed to the library are only borrowed during the call, caller keeps the ownership
+// error is a LinkageSimError code, linkage_sim_last_error_message describes the last error on the thread
+
+impl From<SolveErr> for FfiError {
+    fn from(error: SolveErr) -> Self {
+        let code = match error {
+            SolveErr::CannotAssemble{..} | SolveErr::TooMuchConstraint{..} => LinkageSimError::CannotAssemble,
+            SolveErr::NoAnker | SolveErr::SolveHintshortage{..} | SolveErr::LinkageHintshortage{..} | SolveErr::InvalidInput{..} => LinkageSimError::InvalidInput,
+            SolveErr::InvalidConnection{..} | SolveErr::InvalidJoint{..} => LinkageSimError::IndexOutOfRange,
+            SolveErr::NotSolved => LinkageSimError::NotSolved,
+        };
+        FfiError::new(code, error.to_string())
+    }
+}
+
+// build the mechanism from flat arrays, counts are summed up to find each linkage and joint in the flat arrays
+fn mechanism_from_arrays(points: &[f64], num_points: &[usize], connection_linkages: &[usize], connection_indexes: &[usize], num_connections: &[usize]) -> Result<Mechanism, FfiError> {
+    let mut mechanism = Mechanism::new();
+    let mut points = points.chunks_exact(2).map(|point| Vector2::new(point[0], point[1]));
+    for &count in num_points {
+        let linkage_points: Vec<Vector2<f64>> = points.by_ref().take(count).collect();
+        if linkage_points.iter().any(|point| !point.x.is_finite() || !point.y.is_finite()) {
+            return Err(FfiError::new(LinkageSimError::InvalidDimension, format!("point of linkage {} is not finite", mechanism.get_linkage_count())));
         }
-        Ok(Self)
+        mechanism.add_linkage(Linkage::from_points(&linkage_points));
     }
-}
\ No newline at end of file
+    let mut connections = connection_linkages.iter().zip(connection_indexes);
+    for &count in num_connections {
+        let joint = connections.by_ref().take(count).fold(PinJoint::new(), |joint, (&linkage, &index)| joint.add_connection(linkage, index));
+        mechanism.add_joint(joint)?;
+    }
+    Ok(mechanism)
+}
+
+// sum of the counts, None on overflow
+fn sum_counts(counts: &[usize]) -> Option<usize> {
+    counts.iter().try_fold(0usize, |sum, &count| sum.checked_add(count))
+}
+
+/// points : x and y of the joints of every linkage in its local frame, linkage by linkage (2 * sum of num_points doubles)
+/// num_points : number of joints of each linkage (num_linkages elements), lines of the linkage connect consecutive joints
+/// connection_linkages and connection_indexes : (linkage index, joint index in the linkage) connected by each pin joint, joint by joint (sum of num_connections elements)
+/// num_connections : number of connections of each pin joint (num_joints elements)
+/// null is returned if the mechanism cannot be created, see linkage_sim_last_error_message
+///
+/// # Safety
+///
+/// every pointer must be null or point to the number of readable elements above, pointers of empty arrays can be null.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_new(points: *const f64, num_points: *const usize, num_linkages: usize, connection_linkages: *const usize, connection_indexes: *const usize, num_connections: *const usize, num_joints: usize) -> *mut Mechanism {
+    crate::ffi::catch_panic(std::ptr::null_mut(), || {
+        // None if the pointer is null, empty array can be null
+        fn slice<'a, T>(pointer: *const T, len: usize) -> Option<&'a [T]> {
+            if len == 0 {return Some(&[]);}
+            (!pointer.is_null()).then(|| unsafe { std::slice::from_raw_parts(pointer, len) })
+        }
+        let (Some(num_points), Some(num_connections)) = (slice(num_points, num_linkages), slice(num_connections, num_joints)) else {
+            FfiError::new(LinkageSimError::NullPointer, "num_points or num_connections is null").into_code();
+            return std::ptr::null_mut();
+        };
+        let (Some(total_points), Some(total_connections)) = (sum_counts(num_points).and_then(|total| total.checked_mul(2)), sum_counts(num_connections)) else {
+            FfiError::new(LinkageSimError::InvalidInput, "number of points or connections is too large").into_code();
+            return std::ptr::null_mut();
+        };
+        let (Some(points), Some(connection_linkages), Some(connection_indexes)) = (slice(points, total_points), slice(connection_linkages, total_connections), slice(connection_indexes, total_connections)) else {
+            FfiError::new(LinkageSimError::NullPointer, "points, connection_linkages or connection_indexes is null").into_code();
+            return std::ptr::null_mut();
+        };
+        match mechanism_from_arrays(points, num_points, connection_linkages, connection_indexes, num_connections) {
+            Ok(mechanism) => Box::into_raw(Box::new(mechanism)),
+            Err(error) => {
+                error.into_code();
+                std::ptr::null_mut()
+            },
+        }
+    })
+}
+
+/// fix the joint to the ground at (x, y)
+///
+/// # Safety
+///
+/// mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_fix_joint(mechanism: *mut Mechanism, joint: usize, x: f64, y: f64) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let mechanism = match unsafe { mechanism.as_mut() } {
+            Some(mechanism) => mechanism,
+            None => return FfiError::new(LinkageSimError::NullPointer, "mechanism is null").into_code(),
+        };
+        if !x.is_finite() || !y.is_finite() {
+            return FfiError::new(LinkageSimError::InvalidInput, format!("point ({}, {}) is not finite", x, y)).into_code();
+        }
+        match mechanism.fix_joint(joint, Point2::new(x, y)) {
+            Ok(()) => LinkageSimError::Ok as isize,
+            Err(error) => FfiError::from(error).into_code(),
+        }
+    })
+}
+
+/// add SolveHint, the joint of the dyad is solved to the side where (joint_to_0 - joint_from) x (joint_to_1 - joint_from) is positive, negative if invert
+///
+/// # Safety
+///
+/// mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_add_hint(mechanism: *mut Mechanism, joint_from: usize, joint_to_0: usize, joint_to_1: usize, invert: bool) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let mechanism = match unsafe { mechanism.as_mut() } {
+            Some(mechanism) => mechanism,
+            None => return FfiError::new(LinkageSimError::NullPointer, "mechanism is null").into_code(),
+        };
+        if let Some(&joint) = [joint_from, joint_to_0, joint_to_1].iter().find(|&&joint| joint >= mechanism.get_joint_count()) {
+            return FfiError::from(SolveErr::InvalidJoint{joint}).into_code();
+        }
+        mechanism.add_hint(SolveHint{joint_index_from: joint_from, joint_index_to: [joint_to_0, joint_to_1], invert});
+        LinkageSimError::Ok as isize
+    })
+}
+
+/// solve the mechanism at the inputs (num_inputs elements), on error the mechanism keeps the previous solution
+///
+/// # Safety
+///
+/// mechanism must be null or a live handle returned by mechanism_new, not used by another thread during the call,
+/// inputs must be null or point to num_inputs readable MechInput (can be null if num_inputs is 0).
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_solve(mechanism: *mut Mechanism, inputs: *const MechInput, num_inputs: usize) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let mechanism = match unsafe { mechanism.as_mut() } {
+            Some(mechanism) if num_inputs == 0 || !inputs.is_null() => mechanism,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "mechanism or inputs is null").into_code(),
+        };
+        let inputs = if num_inputs == 0 {&[][..]} else {unsafe { std::slice::from_raw_parts(inputs, num_inputs) }};
+        match mechanism.solve(inputs) {
+            Ok(()) => LinkageSimError::Ok as isize,
+            Err(error) => FfiError::from(error).into_code(),
+        }
+    })
+}
+
+/// x and y of every joint are written to buffer in the order of the joints (2 * capacity doubles)
+/// num_joints : number of joints of the mechanism, written even if capacity is too small so that the caller can allocate the buffer
+/// returns BufferTooSmall and writes no joint if capacity < num_joints
+///
+/// # Safety
+///
+/// mechanism must be null or a live handle returned by mechanism_new, num_joints must be null or point to a writable size_t, buffer must be null or point to 2 * capacity writable double.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_get_joints(mechanism: *const Mechanism, buffer: *mut f64, capacity: usize, num_joints: *mut usize) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let mechanism = match unsafe { mechanism.as_ref() } {
+            Some(mechanism) if !num_joints.is_null() => mechanism,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "mechanism or num_joints is null").into_code(),
+        };
+        let joints = match mechanism.get_joints() {
+            Ok(joints) => joints,
+            Err(error) => return FfiError::from(error).into_code(),
+        };
+        unsafe { num_joints.write(joints.len()) };
+        if capacity < joints.len() {
+            return FfiError::new(LinkageSimError::BufferTooSmall, format!("{} joints do not fit in buffer of capacity {}", joints.len(), capacity)).into_code();
+        }
+        if buffer.is_null() {
+            return FfiError::new(LinkageSimError::NullPointer, "buffer is null").into_code();
+        }
+        let buffer = unsafe { std::slice::from_raw_parts_mut(buffer, 2 * joints.len()) };
+        for (xy, joint) in buffer.chunks_exact_mut(2).zip(joints) {
+            xy.copy_from_slice(&[joint.0, joint.1]);
+        }
+        LinkageSimError::Ok as isize
+    })
+}
+
+/// solve the mechanism for every angle of inputs[input], the handle itself is not changed
+/// angles and errors have num_samples elements, errors[i] is the LinkageSimError of sample i
+/// joints : optional (can be null), x and y of the joints of sample i are written from joints[2 * i * num_joints] in the order of the joints
+/// joints_capacity is counted in joints and must be at least num_samples * number of joints
+/// joints of failed samples are not written
+/// returns error only if the arguments are invalid, failure of each sample is in its error
+///
+/// # Safety
+///
+/// mechanism must be null or a live handle returned by mechanism_new, inputs must be null or point to num_inputs readable MechInput,
+/// angles must be null or point to num_samples readable double, errors must be null or point to num_samples writable ptrdiff_t,
+/// joints must be null or point to 2 * joints_capacity writable double.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_solve_sweep(mechanism: *const Mechanism, inputs: *const MechInput, num_inputs: usize, input: usize, angles: *const f64, num_samples: usize, errors: *mut isize, joints: *mut f64, joints_capacity: usize) -> isize {
+    crate::ffi::catch_panic(LinkageSimError::Panic as isize, || {
+        let mechanism = match unsafe { mechanism.as_ref() } {
+            Some(mechanism) if !inputs.is_null() && !angles.is_null() && !errors.is_null() => mechanism,
+            _ => return FfiError::new(LinkageSimError::NullPointer, "mechanism, inputs, angles or errors is null").into_code(),
+        };
+        if input >= num_inputs {
+            return FfiError::new(LinkageSimError::IndexOutOfRange, format!("input {} is not less than num_inputs {}", input, num_inputs)).into_code();
+        }
+        let num_joints = mechanism.get_joint_count();
+        let total_joints = match num_samples.checked_mul(num_joints).filter(|total| total.checked_mul(2).is_some()) {
+            Some(total_joints) => total_joints,
+            None => return FfiError::new(LinkageSimError::InvalidInput, format!("num_samples {} is too large", num_samples)).into_code(),
+        };
+        let mut joints = if joints.is_null() {
+            None
+        } else if joints_capacity < total_joints {
+            return FfiError::new(LinkageSimError::BufferTooSmall, format!("{} joints do not fit in buffer of capacity {}", total_joints, joints_capacity)).into_code();
+        } else {
+            Some(unsafe { std::slice::from_raw_parts_mut(joints, 2 * total_joints) })
+        };
+        let mut inputs = unsafe { std::slice::from_raw_parts(inputs, num_inputs) }.to_vec();
+        let angles = unsafe { std::slice::from_raw_parts(angles, num_samples) };
+        let errors = unsafe { std::slice::from_raw_parts_mut(errors, num_samples) };
+        let mut solved = mechanism.clone();
+        for (i, error) in errors.iter_mut().enumerate() {
+            inputs[input].angle = angles[i];
+            *error = match solved.solve(&inputs).and_then(|()| solved.get_joints()) {
+                Ok(sample_joints) => {
+                    if let Some(joints) = joints.as_deref_mut() {
+                        for (xy, joint) in joints[2 * i * num_joints..2 * (i + 1) * num_joints].chunks_exact_mut(2).zip(sample_joints) {
+                            xy.copy_from_slice(&[joint.0, joint.1]);
+                        }
+                    }
+                    LinkageSimError::Ok as isize
+                },
+                Err(sample_error) => FfiError::from(sample_error).into_code(),
+            };
+        }
+        LinkageSimError::Ok as isize
+    })
+}
+
+/// # Safety
+///
+/// mechanism must be null or a handle returned by mechanism_new that is not freed yet, it must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn mechanism_free(mechanism: *mut Mechanism) {
+    if mechanism.is_null() {return;}
+    crate::ffi::catch_panic((), || unsafe { drop(Box::from_raw(mechanism)) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mech_solver::synthesis::{FourBarDimension, FourBarBranch};
+
+    const FOUR_BAR: FourBarDimension = FourBarDimension{
+        ground_a: (0.0, 0.0),
+        ground_b: (2.0, 0.0),
+        crank: 0.8,
+        coupler: 2.2,
+        rocker: 1.5,
+        coupler_point: (1.0, 0.5),
+    };
+
+    fn crank(angle: f64) -> [MechInput; 1] {
+        [MechInput{joint_index: 0, linkage_index: 1, angle}]
+    }
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn four_bar_matches_four_bar_dimension() {
+        for branch in [FourBarBranch::Left, FourBarBranch::Right] {
+            let mut mechanism = FOUR_BAR.to_mechanism(branch);
+            for i in 0..12 {
+                let angle = 2.0 * PI * i as f64 / 12.0;
+                let position = FOUR_BAR.solve(angle, branch).unwrap();
+                mechanism.solve(&crank(angle)).unwrap();
+                let joints = mechanism.get_joints().unwrap();
+                assert_close(joints[0], FOUR_BAR.ground_a);
+                assert_close(joints[1], FOUR_BAR.ground_b);
+                assert_close(joints[2], position.a);
+                assert_close(joints[3], position.b);
+                assert_close(joints[4], position.coupler_point);
+                let coupler_theta = mechanism.get_linkage_angle(2).unwrap();
+                assert!((PI - (PI - (coupler_theta - position.coupler_theta)).rem_euclid(2.0 * PI)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn transmission_angle_is_angle_between_coupler_and_rocker() {
+        let mut mechanism = FOUR_BAR.to_mechanism(FourBarBranch::Left);
+        for i in 0..12 {
+            let angle = 2.0 * PI * i as f64 / 12.0;
+            let report = mechanism.solve_with_report(&crank(angle), 3, 40f64.to_radians()).unwrap();
+            // law of cosines on coupler, rocker and the diagonal from A to ground_b
+            let a = report.joints[2];
+            let diagonal = (FOUR_BAR.ground_b.0 - a.0).powi(2) + (FOUR_BAR.ground_b.1 - a.1).powi(2);
+            let expected = ((FOUR_BAR.coupler.powi(2) + FOUR_BAR.rocker.powi(2) - diagonal) / (2.0 * FOUR_BAR.coupler * FOUR_BAR.rocker)).acos();
+            assert_eq!(report.transmission_angles.len(), 1);
+            let (joint, transmission_angle) = report.transmission_angles[0];
+            assert_eq!(joint, 3);
+            assert!((transmission_angle - expected).abs() < 1e-9, "{} != {}", transmission_angle, expected);
+            let poor = expected < 40f64.to_radians() || expected > 140f64.to_radians();
+            assert_eq!(report.poor_transmission == vec![3], poor);
+        }
+    }
+
+    #[test]
+    fn parallelogram_has_unit_mechanical_advantage() {
+        let parallelogram = FourBarDimension{
+            ground_a: (0.0, 0.0),
+            ground_b: (2.0, 0.0),
+            crank: 1.0,
+            coupler: 2.0,
+            rocker: 1.0,
+            coupler_point: (1.0, 0.0),
+        };
+        let mut mechanism = parallelogram.to_mechanism(FourBarBranch::Left);
+        let report = mechanism.solve_with_report(&crank(PI / 2.0), 3, 40f64.to_radians()).unwrap();
+        assert!((report.mechanical_advantage - 1.0).abs() < 1e-6, "{}", report.mechanical_advantage);
+        assert!((report.transmission_angles[0].1 - PI / 2.0).abs() < 1e-9);
+        assert!(report.poor_transmission.is_empty());
+    }
+
+    #[test]
+    fn adding_hint_clears_solution() {
+        let mut mechanism = FOUR_BAR.to_mechanism(FourBarBranch::Left);
+        mechanism.solve(&crank(0.7)).unwrap();
+        mechanism.add_hint(SolveHint{joint_index_from: 2, joint_index_to: [1, 3], invert: true});
+        assert_eq!(mechanism.get_joints().unwrap_err(), SolveErr::NotSolved);
+        assert_eq!(mechanism.get_transmission_angles().unwrap_err(), SolveErr::NotSolved);
+    }
+
+    #[test]
+    fn invalid_mechanism_is_error() {
+        let mut mechanism = FOUR_BAR.to_mechanism(FourBarBranch::Left);
+        assert_eq!(mechanism.get_joints().unwrap_err(), SolveErr::NotSolved);
+        // crank is free without input
+        assert_eq!(mechanism.solve(&[]).unwrap_err(), SolveErr::SolveHintshortage{at: 2});
+        // ground is already placed by its fixed joints
+        assert_eq!(mechanism.solve(&[MechInput{joint_index: 0, linkage_index: 0, angle: 0.5}]).unwrap_err(), SolveErr::TooMuchConstraint{at: 1});
+        // coupler is not connected to ground_a
+        assert_eq!(mechanism.solve(&[MechInput{joint_index: 0, linkage_index: 2, angle: 0.5}]).unwrap_err(), SolveErr::InvalidInput{at: 0});
+        assert_eq!(mechanism.add_joint(PinJoint::from_linkage([1, 9], [0, 0])).unwrap_err(), SolveErr::InvalidConnection{joint: 5, linkage: 9, index: 0});
+        assert_eq!(mechanism.add_joint(PinJoint::from_linkage([1], [2])).unwrap_err(), SolveErr::InvalidConnection{joint: 5, linkage: 1, index: 2});
+
+        let short = FourBarDimension{coupler: 0.5, rocker: 0.5, ..FOUR_BAR};
+        assert_eq!(short.to_mechanism(FourBarBranch::Left).solve(&crank(0.0)).unwrap_err(), SolveErr::CannotAssemble{at: 3});
+
+        let mut floating = Mechanism::from_linkages(vec![Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)])]);
+        floating.add_joint(PinJoint::from_linkage([0], [0])).unwrap();
+        assert_eq!(floating.solve(&[]).unwrap_err(), SolveErr::NoAnker);
+    }
+
+    #[test]
+    fn handle_api_matches_four_bar() {
+        // linkages and joints of FourBarDimension::to_mechanism for FOUR_BAR
+        let points = [0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.8, 0.0, 0.0, 0.0, 2.2, 0.0, 1.0, 0.5, 0.0, 0.0, 1.5, 0.0];
+        let num_points = [2, 2, 3, 2];
+        let connection_linkages = [0, 1, 0, 3, 1, 2, 2, 3, 2];
+        let mut connection_indexes = [0, 0, 1, 0, 1, 0, 1, 1, 2];
+        let num_connections = [2, 2, 2, 2, 1];
+        let inputs = crank(0.7);
+        let code = |error: LinkageSimError| error as isize;
+        unsafe {
+            let mechanism = mechanism_new(points.as_ptr(), num_points.as_ptr(), 4, connection_linkages.as_ptr(), connection_indexes.as_ptr(), num_connections.as_ptr(), 5);
+            assert!(!mechanism.is_null());
+            assert_eq!(mechanism_fix_joint(mechanism, 0, 0.0, 0.0), code(LinkageSimError::Ok));
+            assert_eq!(mechanism_fix_joint(mechanism, 1, 2.0, 0.0), code(LinkageSimError::Ok));
+            assert_eq!(mechanism_fix_joint(mechanism, 5, 2.0, 0.0), code(LinkageSimError::IndexOutOfRange));
+            assert_eq!(mechanism_add_hint(mechanism, 2, 1, 3, false), code(LinkageSimError::Ok));
+            let mut num_joints = 0;
+            assert_eq!(mechanism_get_joints(mechanism, std::ptr::null_mut(), 0, &mut num_joints), code(LinkageSimError::NotSolved));
+            assert_eq!(mechanism_solve(mechanism, inputs.as_ptr(), 1), code(LinkageSimError::Ok));
+            let mut buffer = [0.0; 10];
+            assert_eq!(mechanism_get_joints(mechanism, buffer.as_mut_ptr(), 4, &mut num_joints), code(LinkageSimError::BufferTooSmall));
+            assert_eq!(num_joints, 5);
+            assert_eq!(mechanism_get_joints(mechanism, buffer.as_mut_ptr(), 5, &mut num_joints), code(LinkageSimError::Ok));
+            let position = FOUR_BAR.solve(0.7, FourBarBranch::Left).unwrap();
+            assert_close((buffer[6], buffer[7]), position.b);
+            assert_close((buffer[8], buffer[9]), position.coupler_point);
+
+            let angles = [0.0, 1.0, f64::NAN];
+            let mut errors = [0; 3];
+            let mut joints = [0.0; 30];
+            assert_eq!(mechanism_solve_sweep(mechanism, inputs.as_ptr(), 1, 0, angles.as_ptr(), 3, errors.as_mut_ptr(), joints.as_mut_ptr(), 14), code(LinkageSimError::BufferTooSmall));
+            assert_eq!(mechanism_solve_sweep(mechanism, inputs.as_ptr(), 1, 0, angles.as_ptr(), 3, errors.as_mut_ptr(), joints.as_mut_ptr(), 15), code(LinkageSimError::Ok));
+            assert_eq!(errors, [code(LinkageSimError::Ok), code(LinkageSimError::Ok), code(LinkageSimError::InvalidInput)]);
+            assert_close((joints[16], joints[17]), FOUR_BAR.solve(1.0, FourBarBranch::Left).unwrap().b);
+            mechanism_free(mechanism);
+
+            // joint 2 of the crank does not exist
+            connection_indexes[4] = 2;
+            assert!(mechanism_new(points.as_ptr(), num_points.as_ptr(), 4, connection_linkages.as_ptr(), connection_indexes.as_ptr(), num_connections.as_ptr(), 5).is_null());
+            assert!(mechanism_new(std::ptr::null(), num_points.as_ptr(), 4, connection_linkages.as_ptr(), connection_indexes.as_ptr(), num_connections.as_ptr(), 5).is_null());
+        }
+    }
+}
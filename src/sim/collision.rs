@@ -0,0 +1,134 @@
+use super::{Mechanism, SolveErr, rotate};
+use crate::mech_solver::limit_position::{self, SweepRange};
+use crate::scissor_solver::collision::{Segment, segment_distance};
+
+// contact between lines of two linkages treated as capsules (segments with thickness)
+// lines are indexes in the linkage, distance is the distance between center lines
+#[derive(Debug, Clone, Copy)]
+pub struct MechanismContact{
+    pub linkages: [usize; 2],
+    pub lines: [usize; 2],
+    pub distance: f64,
+}
+
+impl Mechanism{
+    // lines of every linkage in the solved mechanism, lines[linkage][line]
+    pub fn get_lines(&self) -> Result<Vec<Vec<Segment>>, SolveErr>{
+        let state = self.get_state()?;
+        Ok(self.linkages.iter().enumerate().map(|(linkage, linkage_lines)| {
+            linkage_lines.lines.iter().map(|line| {
+                let [from, to] = line.map(|point| rotate(point, state.angles[linkage]) + state.offsets[linkage]);
+                ((from.x, from.y), (to.x, to.y))
+            }).collect()
+        }).collect())
+    }
+    // contacts in the solved mechanism
+    // linkages connected by a pin joint always touch at the pin, so they are not checked
+    pub fn find_collisions(&self, thickness: f64) -> Result<Vec<MechanismContact>, SolveErr>{
+        let lines = self.get_lines()?;
+        let connected = |i: usize, j: usize| self.joints.iter().any(|joint| {
+            joint.connections.iter().any(|&(linkage, _)| linkage == i) && joint.connections.iter().any(|&(linkage, _)| linkage == j)
+        });
+        let mut contacts = Vec::new();
+        for i in 0..lines.len(){
+            for j in i + 1..lines.len(){
+                if connected(i, j) {continue;}
+                for (line_i, &s1) in lines[i].iter().enumerate(){
+                    for (line_j, &s2) in lines[j].iter().enumerate(){
+                        let distance = segment_distance(s1, s2);
+                        if distance < thickness {
+                            contacts.push(MechanismContact{linkages: [i, j], lines: [line_i, line_j], distance});
+                        }
+                    }
+                }
+            }
+        }
+        Ok(contacts)
+    }
+    // sweep the angle of inputs[input] of the last solve over angle_range and find the first angle where contact occurs
+    // return the angle at the contact and the contacts there, start of the range is returned if it has contact
+    // return None if no contact occurs or the mechanism cannot be assembled before contact
+    pub fn find_first_collision(&self, thickness: f64, input: usize, angle_range: SweepRange) -> Result<Option<(f64, Vec<MechanismContact>)>, SolveErr>{
+        let state = self.get_state()?;
+        if input >= state.inputs.len() {return Err(SolveErr::InvalidInput{at: input});}
+        let SweepRange{start: start_angle, end: end_angle, steps, tolerance} = angle_range;
+        let contacts_at = |angle: f64| -> Result<Vec<MechanismContact>, SolveErr> {
+            let mut inputs = state.inputs.clone();
+            inputs[input].angle = angle;
+            let mut mechanism = self.clone();
+            mechanism.solve(&inputs)?;
+            mechanism.find_collisions(thickness)
+        };
+        let start_contacts = contacts_at(start_angle)?;
+        if !start_contacts.is_empty() {return Ok(Some((start_angle, start_contacts)));}
+        let is_clear = |angle: f64| contacts_at(angle).is_ok_and(|contacts| contacts.is_empty());
+        let (clear, lost) = match limit_position::bracket_limit(start_angle, end_angle, steps, is_clear){
+            Some(bracket) => bracket,
+            None => return Ok(None),
+        };
+        // assembly is lost before contact
+        if contacts_at(lost).is_err() {return Ok(None);}
+        // narrow down where the contact starts, mechanism must be assembled while bisecting
+        let (_, contact) = limit_position::bisect_limit(clear, lost, tolerance, |angle| {
+            match contacts_at(angle){
+                Ok(contacts) => contacts.is_empty(),
+                Err(_) => true,
+            }
+        });
+        Ok(contacts_at(contact).ok().map(|contacts| (contact, contacts)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use cgmath::{Vector2, Point2};
+
+    use super::*;
+    use crate::sim::{Linkage, PinJoint, MechInput};
+
+    // two cranks of length 1.5 on the ground pins (0, 0) and (2, 0), the second one stands upright
+    fn two_cranks(angle: f64) -> Mechanism {
+        let mut mechanism = Mechanism::from_linkages(vec![
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0)]),
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(1.5, 0.0)]),
+            Linkage::from_points(&[Vector2::new(0.0, 0.0), Vector2::new(1.5, 0.0)]),
+        ]);
+        mechanism.add_joint(PinJoint::from_linkage([0, 1], [0, 0]).fix(Point2::new(0.0, 0.0))).unwrap();
+        mechanism.add_joint(PinJoint::from_linkage([0, 2], [1, 0]).fix(Point2::new(2.0, 0.0))).unwrap();
+        mechanism.add_joint(PinJoint::from_linkage([1], [1])).unwrap();
+        mechanism.add_joint(PinJoint::from_linkage([2], [1])).unwrap();
+        mechanism.solve(&[
+            MechInput{joint_index: 0, linkage_index: 1, angle},
+            MechInput{joint_index: 1, linkage_index: 2, angle: PI / 2.0},
+        ]).unwrap();
+        mechanism
+    }
+
+    #[test]
+    fn cranks_touch_when_the_tip_reaches_the_thickness() {
+        let mechanism = two_cranks(0.1);
+        let contacts = mechanism.find_collisions(0.6).unwrap();
+        // cranks share pins only with the ground
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].linkages, [1, 2]);
+        assert!((contacts[0].distance - (2.0 - 1.5 * 0.1f64.cos())).abs() < 1e-9);
+        assert!(two_cranks(PI / 2.0).find_collisions(0.6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sweep_finds_the_first_contact() {
+        let mechanism = two_cranks(PI);
+        let range = SweepRange{start: PI, end: 0.0, steps: 100, tolerance: 1e-12};
+        let (angle, contacts) = mechanism.find_first_collision(0.6, 0, range).unwrap().unwrap();
+        // tip of the first crank is 0.6 away from the upright one
+        assert!((angle - (1.4f64 / 1.5).acos()).abs() < 1e-9, "{}", angle);
+        assert_eq!(contacts[0].linkages, [1, 2]);
+        assert!(mechanism.find_first_collision(0.1, 0, SweepRange{end: PI / 2.0, ..range}).unwrap().is_none());
+        assert_eq!(mechanism.find_first_collision(0.6, 2, range).unwrap_err(), SolveErr::InvalidInput{at: 2});
+        // contact at the start of the sweep
+        let (angle, _) = two_cranks(0.1).find_first_collision(0.6, 0, SweepRange{start: 0.1, ..range}).unwrap().unwrap();
+        assert_eq!(angle, 0.1);
+    }
+}